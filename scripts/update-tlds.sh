@@ -0,0 +1,33 @@
+#!/usr/bin/env bash
+# Regenerates src/impl/type/tlds.txt from the ICANN section of the Public Suffix List.
+#
+# Usage: scripts/update-tlds.sh [public_suffix_list.dat]
+#
+# Without an argument the current list is downloaded from publicsuffix.org.
+set -euo pipefail
+
+url=https://publicsuffix.org/list/public_suffix_list.dat
+out="$(dirname "$0")/../src/impl/type/tlds.txt"
+
+if [[ $# -gt 0 ]]; then
+  list=$1
+else
+  list=$(mktemp)
+  trap 'rm -f "$list"' EXIT
+  # -R keeps the server's modification time, which dates the snapshot below
+  curl -fsSLR -o "$list" "$url"
+fi
+
+{
+  echo "// Top-level domains from the ICANN section of the Public Suffix List, $url"
+  echo "// Snapshot of $(date -u -r "$list" +%F). Regenerate with scripts/update-tlds.sh."
+  echo "//"
+  echo "// This Source Code Form is subject to the terms of the Mozilla Public"
+  echo "// License, v. 2.0. If a copy of the MPL was not distributed with this"
+  echo "// file, You can obtain one at https://mozilla.org/MPL/2.0/."
+  sed -n '/===BEGIN ICANN DOMAINS===/,/===END ICANN DOMAINS===/p' "$list" |
+    grep -v -e '^//' -e '^$' |
+    sed 's/^[*!]\.\{0,1\}//' |
+    grep -v '\.' |
+    LC_ALL=C sort -u
+} >"$out"
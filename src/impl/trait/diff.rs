@@ -0,0 +1,145 @@
+use crate::r#trait::{Diff, Text};
+use ropey::RopeSlice;
+use std::iter;
+use tower_lsp::lsp_types::{Range, TextEdit};
+
+impl Diff for RopeSlice<'_> {
+  fn diff(&self, range: Range, new_text: &str) -> Vec<TextEdit> {
+    let byte_range = self.range(range);
+    let old_text = self.slice(byte_range.clone()).to_string();
+    let (old_lines, new_lines) = (lines(&old_text), lines(new_text));
+    let hunks = if old_lines.len() == new_lines.len() {
+      iter::zip(old_lines, new_lines).collect()
+    } else {
+      vec![((0, old_text.as_str()), (0, new_text))]
+    };
+    hunks
+      .into_iter()
+      .filter(|((_, old), (_, new))| old != new)
+      .map(|((offset, old), (_, new))| {
+        let (old_content, new_content) = (content(old), content(new));
+        // An edit must not start or end inside a `\r\n` pair, so a changed line ending is
+        // replaced whole.
+        let endings_differ = old[old_content.len()..] != new[new_content.len()..];
+        let (old_common, new_common) = if endings_differ {
+          (old_content, new_content)
+        } else {
+          (old, new)
+        };
+        let prefix = iter::zip(old_common.char_indices(), new_common.chars())
+          .find(|((_, a), b)| a != b)
+          .map(|((idx, _), _)| idx)
+          .unwrap_or(old_common.len().min(new_common.len()));
+        let suffix = if endings_differ {
+          0
+        } else {
+          iter::zip(old[prefix..].chars().rev(), new[prefix..].chars().rev())
+            .take_while(|(a, b)| a == b)
+            .map(|(c, _)| c.len_utf8())
+            .sum::<usize>()
+        };
+        let start = byte_range.start + offset + prefix;
+        TextEdit {
+          range: self.lsp_range(start..byte_range.start + offset + old.len() - suffix),
+          new_text: new[prefix..new.len() - suffix].to_string(),
+        }
+      })
+      .collect()
+  }
+}
+
+/// A line without its line ending.
+fn content(line: &str) -> &str {
+  line.trim_end_matches(['\r', '\n'])
+}
+
+/// Lines with their endings and byte offsets, split like `LineType::LF_CR`.
+fn lines(text: &str) -> Vec<(usize, &str)> {
+  let bytes = text.as_bytes();
+  let mut lines = Vec::new();
+  let mut start = 0;
+  for (idx, byte) in bytes.iter().enumerate() {
+    let end = match byte {
+      b'\n' => idx + 1,
+      b'\r' if bytes.get(idx + 1) != Some(&b'\n') => idx + 1,
+      _ => continue,
+    };
+    lines.push((start, &text[start..end]));
+    start = end;
+  }
+  if start < text.len() {
+    lines.push((start, &text[start..]));
+  }
+  lines
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use ropey::Rope;
+  use tower_lsp::lsp_types::Position;
+
+  fn apply(text: &str, new_text: &str) -> (Vec<TextEdit>, String) {
+    let rope = Rope::from_str(text);
+    let edits = rope.slice(..).diff(rope.slice(..).range_full(), new_text);
+    let mut output = rope.clone();
+    edits.iter().rev().for_each(|edit| {
+      let range = output.slice(..).range(edit.range);
+      output.remove(range.clone());
+      output.insert(range.start, &edit.new_text);
+    });
+    (edits, output.to_string())
+  }
+
+  #[test]
+  fn test_diff_per_line() {
+    let (edits, output) = apply("a  \nb\nc\t\r\n", "a\nb\nc\r\n");
+    assert_eq!(output, "a\nb\nc\r\n");
+    assert_eq!(
+      edits.iter().map(|edit| edit.range).collect::<Vec<_>>(),
+      [
+        Range::new(Position::new(0, 1), Position::new(0, 3)),
+        Range::new(Position::new(2, 1), Position::new(2, 2)),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_diff_line_endings() {
+    let (edits, output) = apply("a\r\nb\r\n", "a\nb\n");
+    assert_eq!(output, "a\nb\n");
+    assert_eq!(
+      edits.iter().map(|edit| edit.range).collect::<Vec<_>>(),
+      [
+        Range::new(Position::new(0, 1), Position::new(1, 0)),
+        Range::new(Position::new(1, 1), Position::new(2, 0)),
+      ]
+    );
+    let rope = Rope::from_str("a\rb\r");
+    let source = rope.slice(..);
+    assert_eq!(
+      source
+        .diff(source.range_full(), "a\nb\n")
+        .iter()
+        .map(|edit| edit.range)
+        .collect::<Vec<_>>(),
+      [
+        Range::new(Position::new(0, 1), Position::new(1, 0)),
+        Range::new(Position::new(1, 1), Position::new(2, 0)),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_diff_line_count_changed() {
+    let (edits, output) = apply("a\nb\n\n\n", "a\nb\n");
+    assert_eq!(output, "a\nb\n");
+    assert_eq!(edits.len(), 1);
+    let (edits, output) = apply("👋", "👋\n");
+    assert_eq!(output, "👋\n");
+    assert_eq!(
+      edits[0].range,
+      Range::new(Position::new(0, 2), Position::new(0, 2))
+    );
+  }
+}
@@ -1,3 +1,4 @@
+mod diff;
 mod text;
 mod transform;
 mod with_server;
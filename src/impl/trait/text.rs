@@ -9,16 +9,16 @@ impl Text for &str {
       .lines()
       .enumerate()
       .map(|(line, s)| {
-        (line == position.line as usize)
-          .then(|| {
-            s.encode_utf16()
-              .take(position.character as usize)
-              .pipe(char::decode_utf16)
-              .map(|res| res.unwrap_or(char::REPLACEMENT_CHARACTER))
-              .collect::<String>()
-              .len()
-          })
-          .unwrap_or_else(|| s.len().saturating_add(1))
+        if line == position.line as usize {
+          s.encode_utf16()
+            .take(position.character as usize)
+            .pipe(char::decode_utf16)
+            .map(|res| res.unwrap_or(char::REPLACEMENT_CHARACTER))
+            .collect::<String>()
+            .len()
+        } else {
+          s.len().saturating_add(1)
+        }
       })
       .take((position.line as usize).saturating_add(1))
       .sum::<usize>()
@@ -27,19 +27,27 @@ impl Text for &str {
   fn range_full(&self) -> lsp_types::Range {
     lsp_types::Range {
       start: Position::new(0, 0),
-      end: self
-        .ends_with('\n')
-        .then(|| Position::new(self.lines().count() as u32, 0))
-        .unwrap_or_else(|| {
-          self
-            .lines()
-            .enumerate()
-            .last()
-            .map(|(line, s)| Position::new(line as u32, s.encode_utf16().count() as u32))
-            .unwrap_or(Position::new(0, 0))
-        }),
+      end: if self.ends_with('\n') {
+        Position::new(self.lines().count() as u32, 0)
+      } else {
+        self
+          .lines()
+          .enumerate()
+          .last()
+          .map(|(line, s)| Position::new(line as u32, s.encode_utf16().count() as u32))
+          .unwrap_or(Position::new(0, 0))
+      },
     }
   }
+
+  fn lsp_position(&self, byte_idx: usize) -> lsp_types::Position {
+    let head = &self[..byte_idx];
+    let line_start = head.rfind('\n').map(|idx| idx + 1).unwrap_or(0);
+    Position::new(
+      head.matches('\n').count() as u32,
+      head[line_start..].encode_utf16().count() as u32,
+    )
+  }
 }
 
 impl Text for RopeSlice<'_> {
@@ -48,9 +56,11 @@ impl Text for RopeSlice<'_> {
       .lines(LineType::LF_CR)
       .enumerate()
       .map(|(line, s)| {
-        (line == position.line as usize)
-          .then(|| s.utf16_to_byte_idx(position.character as usize))
-          .unwrap_or_else(|| s.len())
+        if line == position.line as usize {
+          s.utf16_to_byte_idx(position.character as usize)
+        } else {
+          s.len()
+        }
       })
       .take((position.line as usize).saturating_add(1))
       .sum::<usize>()
@@ -67,6 +77,15 @@ impl Text for RopeSlice<'_> {
         .unwrap_or(Position::new(0, 0)),
     }
   }
+
+  fn lsp_position(&self, byte_idx: usize) -> lsp_types::Position {
+    let line = self.byte_to_line_idx(byte_idx, LineType::LF_CR);
+    let line_start = self.line_to_byte_idx(line, LineType::LF_CR);
+    Position::new(
+      line as u32,
+      self.slice(line_start..byte_idx).len_utf16() as u32,
+    )
+  }
 }
 
 #[cfg(test)]
@@ -140,6 +159,37 @@ mod tests {
     assert_eq!(text.as_bytes()[10], b'\n');
   }
 
+  #[test]
+  fn test_lsp_position() {
+    let text = "Hello 😊\n北京 Shanghai\n←↑→↓";
+    assert_eq!(text.lsp_position(0), Position::new(0, 0));
+    assert_eq!(text.lsp_position(10), Position::new(0, 8));
+    assert_eq!(text.lsp_position(11), Position::new(1, 0));
+    assert_eq!(text.lsp_position(27), Position::new(2, 0));
+    assert_eq!(text.lsp_position(text.len()), Position::new(2, 4));
+    let rope = Rope::from_str(text);
+    (0..=text.len())
+      .filter(|&idx| text.is_char_boundary(idx))
+      .for_each(|idx| assert_eq!(rope.slice(..).lsp_position(idx), text.lsp_position(idx)));
+  }
+
+  #[test]
+  fn test_range_full_rope() {
+    ["", "a", "a\n", "a\nb", "a\r\nb\r\n", "👋\n\n"]
+      .map(Rope::from_str)
+      .iter()
+      .for_each(|rope| {
+        assert_eq!(
+          rope.slice(..).range(rope.slice(..).range_full()),
+          0..rope.len()
+        );
+        assert_eq!(
+          rope.slice(..).lsp_range(0..rope.len()),
+          rope.slice(..).range_full()
+        );
+      });
+  }
+
   #[test]
   fn test_range() {
     let text = r#"{ "text": "hello\n👋\n👋world" }"#;
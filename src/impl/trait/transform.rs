@@ -4,56 +4,63 @@ use crate::{
 };
 use futures_lite::FutureExt;
 use serde_json::{Value, from_value, to_value};
-use std::{collections::HashMap, convert::identity};
+use std::collections::HashMap;
 use tap::prelude::*;
 use tower_lsp::{
   jsonrpc::{Error, Result},
   lsp_types::{
-    self, CodeActionOrCommand, CodeActionParams, Command, ExecuteCommandParams, Range, TextEdit,
-    Url, WorkspaceEdit,
+    self, CodeActionOrCommand, CodeActionParams, Command, ExecuteCommandParams, Range, Url,
+    WorkspaceEdit,
   },
 };
 use tracing::error;
 
 impl<T: CommandMeta + Transform> CodeAction for WithServer<'_, T> {
   async fn code_action(&self, params: &CodeActionParams) -> Result<Vec<CodeActionOrCommand>> {
-    ((params.context.only.is_none()
+    let range = if params.context.only.is_none()
       || params
         .context
         .only
         .iter()
-        .flat_map(identity)
-        .any(|kind| self.code_action_kind().contains(kind)))
-      && self
+        .flatten()
+        .any(|kind| self.code_action_kind().contains(kind))
+    {
+      self
         .server()
         .text()
         .get_async(&params.text_document.uri)
         .await
         .as_deref()
-        .map(|rope| self.code_action_condition(rope.slice(..), params.range))
-        .unwrap_or_default())
-    .then(|| {
-      Ok(CodeActionOrCommand::CodeAction(lsp_types::CodeAction {
-        title: String::from(self.command_name()),
-        command: Some(Command {
-          title: String::from(self.command_display_name()),
-          command: String::from(self.command_name()),
-          arguments: Some(vec![
-            to_value(&params.text_document.uri).map_err(|err| {
-              format!("Failed to convert text document URI to JSON value: {err:?}")
-                .pipe(Error::invalid_params)
-            })?,
-            to_value(&params.range).map_err(|err| {
-              format!("Failed to convert range to JSON value: {err:?}").pipe(Error::invalid_params)
-            })?,
-          ]),
-        }),
-        ..Default::default()
-      }))
-    })
-    .transpose()?
-    .pipe(Vec::from_iter)
-    .pipe(Ok)
+        .map(|rope| rope.slice(..))
+        .filter(|source| self.code_action_condition(*source, params.range))
+        .map(|source| self.code_action_range(source, params.range))
+    } else {
+      None
+    };
+    range
+      .map(|range| {
+        Ok(CodeActionOrCommand::CodeAction(lsp_types::CodeAction {
+          title: String::from(self.command_name()),
+          command: Some(Command {
+            title: String::from(self.command_display_name()),
+            command: String::from(self.command_name()),
+            arguments: Some(vec![
+              to_value(&params.text_document.uri).map_err(|err| {
+                format!("Failed to convert text document URI to JSON value: {err:?}")
+                  .pipe(Error::invalid_params)
+              })?,
+              to_value(range).map_err(|err| {
+                format!("Failed to convert range to JSON value: {err:?}")
+                  .pipe(Error::invalid_params)
+              })?,
+            ]),
+          }),
+          ..Default::default()
+        }))
+      })
+      .transpose()?
+      .pipe(Vec::from_iter)
+      .pipe(Ok)
   }
 }
 
@@ -76,29 +83,31 @@ impl<T: Transform + Sync> ExecuteCommand for WithServer<'_, T> {
     let range = params
       .arguments
       .get(1)
+      .filter(|range| !range.is_null())
       .cloned()
       .map(from_value::<Range>)
       .transpose()
       .map_err(|err| {
         Error::invalid_params(format!("Failed to convert range to JSON value: {err:?}"))
       })?;
-    self
+    let rope = self
       .server()
       .text()
       .get_async(&uri)
       .await
-      .ok_or_else(|| Error::internal_error())?
-      .pipe_deref(|rope| {
-        let byte_range = range
-          .map(|range| rope.slice(..).range(range))
-          .unwrap_or(0..rope.len());
-        let range = range.unwrap_or(rope.slice(..).range_full());
-        rope
-          .slice(byte_range)
-          .pipe(|text| self.transform(text))
-          .map(|new_text| TextEdit { range, new_text })
-      })
-      .map(|text_edit| Some(HashMap::from_iter([(uri, vec![text_edit])])))
+      .ok_or_else(Error::internal_error)?
+      .clone();
+    let source = rope.slice(..);
+    let range = range.unwrap_or(source.range_full());
+    self
+      .validate(source, range)
+      .map_err(Error::invalid_params)?;
+    if let Some(value) = self.dry_run(source, range) {
+      return Ok(Some(value));
+    }
+    self
+      .text_edits(source, range)
+      .map(|text_edits| Some(HashMap::from_iter([(uri, text_edits)])))
       .map(|changes| WorkspaceEdit {
         changes,
         ..Default::default()
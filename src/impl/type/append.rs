@@ -0,0 +1,35 @@
+use crate::{
+  r#trait::{Text, Transform},
+  r#type::Append,
+};
+use ropey::RopeSlice;
+use tower_lsp::lsp_types::{CodeActionKind, Range, TextEdit};
+
+impl<T: Transform> Transform for Append<T> {
+  fn code_action_kind(&self) -> Vec<CodeActionKind> {
+    self.0.code_action_kind()
+  }
+
+  fn code_action_condition(&self, source: RopeSlice, range: Range) -> bool {
+    self.0.code_action_condition(source, range)
+  }
+
+  fn transform(&self, text: RopeSlice) -> Option<String> {
+    self
+      .0
+      .transform(text)
+      .map(|result| format!("{text} = {result}"))
+  }
+
+  fn text_edits(&self, source: RopeSlice, range: Range) -> Option<Vec<TextEdit>> {
+    self
+      .0
+      .transform(source.slice(source.range(range)))
+      .map(|result| {
+        vec![TextEdit {
+          range: Range::new(range.end, range.end),
+          new_text: format!(" = {result}"),
+        }]
+      })
+  }
+}
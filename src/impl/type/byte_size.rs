@@ -0,0 +1,190 @@
+use crate::{
+  r#trait::{CommandMeta, Text, Transform},
+  r#type::{HumanizeBytes, ParseBytes},
+};
+use ropey::RopeSlice;
+use tower_lsp::lsp_types::{CodeActionKind, Range};
+
+/// Suffixes as a factor of `numerator / denominator` bytes, including the Kubernetes quantity
+/// suffixes. Exact matches are tried first, so `m` is milli while `M` is mega.
+const UNITS: [(&str, u128, u128); 31] = [
+  ("", 1, 1),
+  ("B", 1, 1),
+  ("n", 1, 1_000_000_000),
+  ("u", 1, 1_000_000),
+  ("m", 1, 1_000),
+  ("k", 1_000, 1),
+  ("K", 1_000, 1),
+  ("kB", 1_000, 1),
+  ("KB", 1_000, 1),
+  ("M", 1_000_000, 1),
+  ("MB", 1_000_000, 1),
+  ("G", 1_000_000_000, 1),
+  ("GB", 1_000_000_000, 1),
+  ("T", 1_000_000_000_000, 1),
+  ("TB", 1_000_000_000_000, 1),
+  ("P", 1_000_000_000_000_000, 1),
+  ("PB", 1_000_000_000_000_000, 1),
+  ("E", 1_000_000_000_000_000_000, 1),
+  ("EB", 1_000_000_000_000_000_000, 1),
+  ("Ki", 1 << 10, 1),
+  ("KiB", 1 << 10, 1),
+  ("Mi", 1 << 20, 1),
+  ("MiB", 1 << 20, 1),
+  ("Gi", 1 << 30, 1),
+  ("GiB", 1 << 30, 1),
+  ("Ti", 1 << 40, 1),
+  ("TiB", 1 << 40, 1),
+  ("Pi", 1 << 50, 1),
+  ("PiB", 1 << 50, 1),
+  ("Ei", 1 << 60, 1),
+  ("EiB", 1 << 60, 1),
+];
+
+impl CommandMeta for HumanizeBytes {
+  fn command_name(&self) -> &'static str {
+    match self {
+      HumanizeBytes::Iec => "text-language-server.humanize-bytes-iec",
+      HumanizeBytes::Si => "text-language-server.humanize-bytes-si",
+    }
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    match self {
+      HumanizeBytes::Iec => "Bytes to IEC (KiB, MiB, GiB)",
+      HumanizeBytes::Si => "Bytes to SI (kB, MB, GB)",
+    }
+  }
+}
+
+impl Transform for HumanizeBytes {
+  fn code_action_kind(&self) -> Vec<CodeActionKind> {
+    vec![CodeActionKind::QUICKFIX, CodeActionKind::SOURCE]
+  }
+
+  fn code_action_condition(&self, source: RopeSlice, range: Range) -> bool {
+    let text = source.slice(source.range(range));
+    self
+      .transform(text)
+      .is_some_and(|result| result != text.to_string().trim())
+  }
+
+  fn transform(&self, text: RopeSlice) -> Option<String> {
+    let bytes = parse(&text.to_string())?;
+    let (base, units) = match self {
+      HumanizeBytes::Iec => (1024.0, ["B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB"]),
+      HumanizeBytes::Si => (1000.0, ["B", "kB", "MB", "GB", "TB", "PB", "EB"]),
+    };
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= base && unit < units.len() - 1 {
+      value /= base;
+      unit += 1;
+    }
+    let value = format!("{value:.2}");
+    let value = value.trim_end_matches('0').trim_end_matches('.');
+    Some(format!("{value} {}", units[unit]))
+  }
+}
+
+impl CommandMeta for ParseBytes {
+  fn command_name(&self) -> &'static str {
+    "text-language-server.parse-bytes"
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    "Size to bytes"
+  }
+}
+
+impl Transform for ParseBytes {
+  fn code_action_kind(&self) -> Vec<CodeActionKind> {
+    vec![CodeActionKind::QUICKFIX, CodeActionKind::SOURCE]
+  }
+
+  fn code_action_condition(&self, source: RopeSlice, range: Range) -> bool {
+    let text = source.slice(source.range(range)).to_string();
+    parse(&text).is_some_and(|bytes| bytes.to_string() != text.trim())
+  }
+
+  fn transform(&self, text: RopeSlice) -> Option<String> {
+    parse(&text.to_string()).map(|bytes| bytes.to_string())
+  }
+}
+
+/// Parses a size such as `1073741824`, `512Mi`, `1.5GB`, `10k` or `1e3` into bytes, rounding
+/// fractional bytes up as Kubernetes does.
+fn parse(text: &str) -> Option<u128> {
+  let text = text.trim().replace(['_', ','], "");
+  let number_len = text
+    .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+    .unwrap_or(text.len());
+  let (number, suffix) = text.split_at(number_len);
+  let (integer, fraction) = number.split_once('.').unwrap_or((number, ""));
+  if integer.is_empty() && fraction.is_empty() {
+    return None;
+  }
+  let mut numerator = format!("{integer}{fraction}").parse::<u128>().ok()?;
+  let mut denominator = 10u128.checked_pow(fraction.len() as u32)?;
+  let suffix = suffix.trim_start();
+  let (unit_numerator, unit_denominator) = match suffix
+    .strip_prefix(['e', 'E'])
+    .filter(|exponent| !exponent.is_empty() && exponent.chars().all(|c| c.is_ascii_digit()))
+  {
+    Some(exponent) => (10u128.checked_pow(exponent.parse().ok()?)?, 1),
+    None => UNITS
+      .iter()
+      .find(|(unit, ..)| *unit == suffix)
+      .or_else(|| {
+        UNITS
+          .iter()
+          .find(|(unit, ..)| unit.ends_with('B') && unit.eq_ignore_ascii_case(suffix))
+      })
+      .map(|&(_, numerator, denominator)| (numerator, denominator))?,
+  };
+  numerator = numerator.checked_mul(unit_numerator)?;
+  denominator = denominator.checked_mul(unit_denominator)?;
+  Some(numerator.div_ceil(denominator))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use ropey::Rope;
+
+  fn humanize(humanize: HumanizeBytes, text: &str) -> Option<String> {
+    humanize.transform(Rope::from_str(text).slice(..))
+  }
+
+  #[test]
+  fn test_humanize_bytes() {
+    assert_eq!(
+      humanize(HumanizeBytes::Iec, "1073741824").as_deref(),
+      Some("1 GiB")
+    );
+    assert_eq!(
+      humanize(HumanizeBytes::Si, "1073741824").as_deref(),
+      Some("1.07 GB")
+    );
+    assert_eq!(
+      humanize(HumanizeBytes::Iec, "1536Mi").as_deref(),
+      Some("1.5 GiB")
+    );
+    assert_eq!(humanize(HumanizeBytes::Si, "999").as_deref(), Some("999 B"));
+    assert_eq!(humanize(HumanizeBytes::Si, "abc"), None);
+  }
+
+  #[test]
+  fn test_parse_bytes() {
+    assert_eq!(parse("512Mi"), Some(536_870_912));
+    assert_eq!(parse("1.5GB"), Some(1_500_000_000));
+    assert_eq!(parse("1.5 gb"), Some(1_500_000_000));
+    assert_eq!(parse("10k"), Some(10_000));
+    assert_eq!(parse("12e6"), Some(12_000_000));
+    assert_eq!(parse("1500m"), Some(2));
+    assert_eq!(parse("1,024 KiB"), Some(1_048_576));
+    assert_eq!(parse("1.0.0"), None);
+    assert_eq!(parse("10 parsecs"), None);
+    assert_eq!(parse("."), None);
+  }
+}
@@ -0,0 +1,399 @@
+use crate::{
+  r#trait::{CommandMeta, Text, Transform},
+  r#type::{Append, Calculate},
+};
+use ropey::RopeSlice;
+use std::{f64::consts, iter::Peekable, str::CharIndices};
+use tower_lsp::lsp_types::{CodeActionKind, Range};
+
+const F64_EXACT_LIMIT: f64 = 9_007_199_254_740_992.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Number {
+  Int(i128),
+  Float(f64),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+  Number(Number),
+  Ident(String),
+  Op(&'static str),
+  Open,
+  Close,
+  Comma,
+}
+
+const OPS: [&str; 14] = [
+  "**", "//", "<<", ">>", "+", "-", "*", "/", "%", "&", "|", "^", "~", "×",
+];
+
+impl CommandMeta for Calculate {
+  fn command_name(&self) -> &'static str {
+    "text-language-server.calculate"
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    "Calculate"
+  }
+}
+
+impl CommandMeta for Append<Calculate> {
+  fn command_name(&self) -> &'static str {
+    "text-language-server.append-calculate"
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    "Append calculation"
+  }
+}
+
+impl Transform for Calculate {
+  fn code_action_kind(&self) -> Vec<CodeActionKind> {
+    vec![CodeActionKind::REFACTOR_REWRITE, CodeActionKind::SOURCE]
+  }
+
+  fn code_action_condition(&self, source: RopeSlice, range: Range) -> bool {
+    let text = source.slice(source.range(range)).to_string();
+    evaluate(&text).is_some_and(|result| result != text.trim())
+  }
+
+  fn transform(&self, text: RopeSlice) -> Option<String> {
+    evaluate(&text.to_string())
+  }
+}
+
+fn evaluate(text: &str) -> Option<String> {
+  let mut parser = Parser {
+    tokens: tokenize(text)?.into_iter().peekable(),
+  };
+  let result = parser.expression(0)?;
+  if parser.tokens.next().is_some() {
+    return None;
+  }
+  match result {
+    Number::Int(int) => Some(int.to_string()),
+    // Past 2^53 a float no longer holds every integer, so printing all digits would invent them
+    Number::Float(float) if !float.is_finite() => None,
+    Number::Float(float) if float.abs() >= F64_EXACT_LIMIT => Some(format!("{float:e}")),
+    Number::Float(float) => Some(float.to_string()),
+  }
+}
+
+fn tokenize(text: &str) -> Option<Vec<Token>> {
+  let mut tokens = Vec::new();
+  let mut chars = text.char_indices().peekable();
+  while let Some(&(idx, c)) = chars.peek() {
+    match c {
+      c if c.is_whitespace() => {
+        chars.next();
+      }
+      '0'..='9' | '.' => tokens.push(Token::Number(number(text, &mut chars)?)),
+      c if c.is_alphabetic() || c == '_' => {
+        let end = take_while(text, &mut chars, |c| c.is_alphanumeric() || c == '_');
+        tokens.push(Token::Ident(text[idx..end].to_ascii_lowercase()));
+      }
+      '(' | ')' | ',' => {
+        chars.next();
+        tokens.push(match c {
+          '(' => Token::Open,
+          ')' => Token::Close,
+          _ => Token::Comma,
+        });
+      }
+      _ => {
+        let op = OPS.into_iter().find(|op| text[idx..].starts_with(op))?;
+        for _ in op.chars() {
+          chars.next();
+        }
+        tokens.push(Token::Op(if op == "×" { "*" } else { op }));
+      }
+    }
+  }
+  Some(tokens)
+}
+
+fn take_while(text: &str, chars: &mut Peekable<CharIndices>, f: impl Fn(char) -> bool) -> usize {
+  while chars.next_if(|&(_, c)| f(c)).is_some() {}
+  chars.peek().map_or(text.len(), |&(idx, _)| idx)
+}
+
+/// Parses decimal, `0x`, `0o` and `0b` integers with `_` separators, and decimal floats.
+fn number(text: &str, chars: &mut Peekable<CharIndices>) -> Option<Number> {
+  let start = chars.peek()?.0;
+  let mut end = take_while(text, chars, |c| {
+    c.is_ascii_alphanumeric() || c == '_' || c == '.'
+  });
+  let radix = match text[start..end].get(..2) {
+    Some("0x" | "0X") => 16,
+    Some("0o" | "0O") => 8,
+    Some("0b" | "0B") => 2,
+    _ => 10,
+  };
+  // A signed exponent such as `2e-3` continues past the sign
+  if radix == 10
+    && text[start..end].ends_with(['e', 'E'])
+    && chars.next_if(|&(_, c)| c == '-' || c == '+').is_some()
+  {
+    end = take_while(text, chars, |c| c.is_ascii_digit());
+  }
+  let literal = text[start..end].replace('_', "");
+  if radix != 10 {
+    i128::from_str_radix(&literal[2..], radix)
+      .ok()
+      .map(Number::Int)
+  } else {
+    literal
+      .parse::<i128>()
+      .map(Number::Int)
+      .or_else(|_| literal.parse::<f64>().map(Number::Float))
+      .ok()
+  }
+}
+
+struct Parser<I: Iterator<Item = Token>> {
+  tokens: Peekable<I>,
+}
+
+impl<I: Iterator<Item = Token>> Parser<I> {
+  /// Precedence climbing with the binary operators ordered as in Python.
+  fn expression(&mut self, min_precedence: u8) -> Option<Number> {
+    let mut lhs = self.unary()?;
+    while let Some(Token::Op(op)) = self.tokens.peek() {
+      let op = *op;
+      let precedence = match op {
+        "|" => 1,
+        "^" => 2,
+        "&" => 3,
+        "<<" | ">>" => 4,
+        "+" | "-" => 5,
+        "*" | "/" | "//" | "%" => 6,
+        _ => return None,
+      };
+      if precedence < min_precedence {
+        break;
+      }
+      self.tokens.next();
+      let rhs = self.expression(precedence + 1)?;
+      lhs = binary(op, lhs, rhs)?;
+    }
+    Some(lhs)
+  }
+
+  fn unary(&mut self) -> Option<Number> {
+    match self.tokens.peek() {
+      Some(Token::Op(op @ ("-" | "+" | "~"))) => {
+        let op = *op;
+        self.tokens.next();
+        let operand = self.unary()?;
+        match (op, operand) {
+          ("-", Number::Int(int)) => int.checked_neg().map(Number::Int),
+          ("-", Number::Float(float)) => Some(Number::Float(-float)),
+          ("~", operand) => Some(Number::Int(!int(operand)?)),
+          (_, operand) => Some(operand),
+        }
+      }
+      _ => self.power(),
+    }
+  }
+
+  fn power(&mut self) -> Option<Number> {
+    let base = self.primary()?;
+    if self.tokens.next_if_eq(&Token::Op("**")).is_some() {
+      let exponent = self.unary()?;
+      binary("**", base, exponent)
+    } else {
+      Some(base)
+    }
+  }
+
+  fn primary(&mut self) -> Option<Number> {
+    match self.tokens.next()? {
+      Token::Number(number) => Some(number),
+      Token::Open => {
+        let value = self.expression(0)?;
+        (self.tokens.next()? == Token::Close).then_some(value)
+      }
+      Token::Ident(ident) if self.tokens.next_if_eq(&Token::Open).is_some() => {
+        let mut args = vec![self.expression(0)?];
+        while self.tokens.next_if_eq(&Token::Comma).is_some() {
+          args.push(self.expression(0)?);
+        }
+        (self.tokens.next()? == Token::Close).then_some(())?;
+        function(&ident, &args)
+      }
+      Token::Ident(ident) => match ident.as_str() {
+        "pi" => Some(Number::Float(consts::PI)),
+        "tau" => Some(Number::Float(consts::TAU)),
+        "e" => Some(Number::Float(consts::E)),
+        _ => None,
+      },
+      _ => None,
+    }
+  }
+}
+
+fn float(number: Number) -> f64 {
+  match number {
+    Number::Int(int) => int as f64,
+    Number::Float(float) => float,
+  }
+}
+
+fn int(number: Number) -> Option<i128> {
+  match number {
+    Number::Int(int) => Some(int),
+    Number::Float(float) if float.fract() == 0.0 && float.abs() < i128::MAX as f64 => {
+      Some(float as i128)
+    }
+    Number::Float(_) => None,
+  }
+}
+
+fn binary(op: &str, lhs: Number, rhs: Number) -> Option<Number> {
+  use Number::{Float, Int};
+  let checked = match (lhs, rhs) {
+    (Int(a), Int(b)) => match op {
+      "+" => a.checked_add(b),
+      "-" => a.checked_sub(b),
+      "*" => a.checked_mul(b),
+      "/" if a.checked_rem(b) == Some(0) => a.checked_div(b),
+      // Floor division and modulo follow the sign of the divisor, as in Python
+      "//" => a
+        .checked_div(b)
+        .zip(a.checked_rem(b))
+        .map(|(q, r)| q - i128::from(r != 0 && (r < 0) != (b < 0))),
+      "%" => a
+        .checked_rem(b)
+        .map(|r| r + b * i128::from(r != 0 && (r < 0) != (b < 0))),
+      "**" => u32::try_from(b).ok().and_then(|b| a.checked_pow(b)),
+      _ => None,
+    },
+    _ => None,
+  };
+  if let Some(int) = checked {
+    return Some(Int(int));
+  }
+  match op {
+    "&" => Some(Int(int(lhs)? & int(rhs)?)),
+    "|" => Some(Int(int(lhs)? | int(rhs)?)),
+    "^" => Some(Int(int(lhs)? ^ int(rhs)?)),
+    // `checked_shl` only rejects shift amounts, so bits shifted out are caught by multiplying.
+    "<<" => int(lhs)?
+      .checked_mul(
+        1i128
+          .checked_shl(u32::try_from(int(rhs)?).ok()?)
+          .filter(|factor| *factor > 0)?,
+      )
+      .map(Int),
+    ">>" => int(lhs)?
+      .checked_shr(u32::try_from(int(rhs)?).ok()?)
+      .map(Int),
+    _ => {
+      let (a, b) = (float(lhs), float(rhs));
+      Some(Float(match op {
+        "+" => a + b,
+        "-" => a - b,
+        "*" => a * b,
+        "/" => a / b,
+        "//" => (a / b).floor(),
+        "%" => a - b * (a / b).floor(),
+        "**" => a.powf(b),
+        _ => return None,
+      }))
+    }
+  }
+}
+
+fn function(name: &str, args: &[Number]) -> Option<Number> {
+  use Number::{Float, Int};
+  let floats = args.iter().copied().map(float).collect::<Vec<_>>();
+  match (name, args, floats.as_slice()) {
+    ("abs", [Int(a)], _) => Some(Int(a.checked_abs()?)),
+    ("min", [first, rest @ ..], _) => rest.iter().try_fold(*first, |min, &arg| {
+      Some(if float(arg) < float(min) { arg } else { min })
+    }),
+    ("max", [first, rest @ ..], _) => rest.iter().try_fold(*first, |max, &arg| {
+      Some(if float(arg) > float(max) { arg } else { max })
+    }),
+    ("pow", [a, b], _) => binary("**", *a, *b),
+    ("floor" | "ceil" | "round" | "trunc", [Int(a)], _) => Some(Int(*a)),
+    (_, _, &[a]) => Some(Float(match name {
+      "abs" => a.abs(),
+      "sqrt" => a.sqrt(),
+      "cbrt" => a.cbrt(),
+      "exp" => a.exp(),
+      "ln" | "log" => a.ln(),
+      "log2" => a.log2(),
+      "log10" => a.log10(),
+      "sin" => a.sin(),
+      "cos" => a.cos(),
+      "tan" => a.tan(),
+      "asin" => a.asin(),
+      "acos" => a.acos(),
+      "atan" => a.atan(),
+      "floor" => return int(Float(a.floor())).map(Int),
+      "ceil" => return int(Float(a.ceil())).map(Int),
+      "round" => return int(Float(a.round())).map(Int),
+      "trunc" => return int(Float(a.trunc())).map(Int),
+      _ => return None,
+    })),
+    (_, _, &[a, b]) => Some(Float(match name {
+      "log" => a.log(b),
+      "atan2" => a.atan2(b),
+      "hypot" => a.hypot(b),
+      _ => return None,
+    })),
+    _ => None,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_evaluate_integers() {
+    assert_eq!(evaluate("3600 * 24 * 7").as_deref(), Some("604800"));
+    assert_eq!(evaluate("(1 << 20) / 4096").as_deref(), Some("256"));
+    assert_eq!(evaluate("0xff & 0b1010 | 0o100").as_deref(), Some("74"));
+    assert_eq!(evaluate("-2 ** 2 + 2 ** 3 ** 2").as_deref(), Some("508"));
+    assert_eq!(evaluate("~0 ^ 5").as_deref(), Some("-6"));
+    assert_eq!(evaluate("-7 // 2").as_deref(), Some("-4"));
+    assert_eq!(evaluate("-7 % 3").as_deref(), Some("2"));
+    assert_eq!(evaluate("7 % -3").as_deref(), Some("-2"));
+    assert_eq!(evaluate("1_000 × 3").as_deref(), Some("3000"));
+    assert_eq!(evaluate("-3 << 4").as_deref(), Some("-48"));
+    assert_eq!(
+      evaluate("1 << 126").as_deref(),
+      Some("85070591730234615865843651857942052864")
+    );
+    assert_eq!(evaluate("3 << 126"), None);
+    assert_eq!(evaluate("1 << 127"), None);
+  }
+
+  #[test]
+  fn test_evaluate_floats_and_functions() {
+    assert_eq!(evaluate("7 / 2").as_deref(), Some("3.5"));
+    assert_eq!(evaluate("1.5e3 + .5").as_deref(), Some("1500.5"));
+    assert_eq!(evaluate("2e-3 * 1000").as_deref(), Some("2"));
+    assert_eq!(
+      evaluate("sqrt(16) + max(1, 2.5, 2)").as_deref(),
+      Some("6.5")
+    );
+    assert_eq!(evaluate("round(pi * 100)").as_deref(), Some("314"));
+    assert_eq!(evaluate("log(8, 2)").as_deref(), Some("3"));
+    assert_eq!(evaluate("2 ** -1").as_deref(), Some("0.5"));
+    assert_eq!(
+      evaluate("2 ** 200").as_deref(),
+      Some("1.6069380442589903e60")
+    );
+    assert_eq!(
+      evaluate("2 ** 53 + 0.5").as_deref(),
+      Some("9.007199254740992e15")
+    );
+    assert_eq!(evaluate("1 / 0"), None);
+    assert_eq!(evaluate("foo(1)"), None);
+    assert_eq!(evaluate("1 +"), None);
+  }
+}
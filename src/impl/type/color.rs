@@ -0,0 +1,382 @@
+use crate::{
+  r#trait::{CommandMeta, Text, Transform},
+  r#type::ColorFormat,
+};
+use ropey::RopeSlice;
+use std::f64::consts::PI;
+use tap::prelude::*;
+use tower_lsp::lsp_types::{CodeActionKind, Range};
+
+/// A gamma-encoded sRGB color with components and alpha in `0.0..=1.0`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Color {
+  r: f64,
+  g: f64,
+  b: f64,
+  alpha: f64,
+}
+
+impl CommandMeta for ColorFormat {
+  fn command_name(&self) -> &'static str {
+    match self {
+      ColorFormat::Hex => "text-language-server.color-hex",
+      ColorFormat::ShortHex => "text-language-server.color-short-hex",
+      ColorFormat::Rgb => "text-language-server.color-rgb",
+      ColorFormat::Hsl => "text-language-server.color-hsl",
+      ColorFormat::Oklch => "text-language-server.color-oklch",
+    }
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    match self {
+      ColorFormat::Hex => "Color to hex",
+      ColorFormat::ShortHex => "Color to short hex",
+      ColorFormat::Rgb => "Color to rgb()",
+      ColorFormat::Hsl => "Color to hsl()",
+      ColorFormat::Oklch => "Color to oklch()",
+    }
+  }
+}
+
+impl Transform for ColorFormat {
+  fn code_action_kind(&self) -> Vec<CodeActionKind> {
+    vec![CodeActionKind::QUICKFIX, CodeActionKind::SOURCE]
+  }
+
+  fn code_action_condition(&self, source: RopeSlice, range: Range) -> bool {
+    let text = source.slice(source.range(range));
+    self
+      .transform(text)
+      .is_some_and(|result| result != text.to_string().trim())
+  }
+
+  fn transform(&self, text: RopeSlice) -> Option<String> {
+    let color = parse(&text.to_string())?;
+    let alpha = if color.alpha < 1.0 {
+      format!(" / {}", number(color.alpha, 3))
+    } else {
+      String::new()
+    };
+    match self {
+      ColorFormat::Hex => color
+        .bytes()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>()
+        .pipe(|hex| format!("#{hex}")),
+      // Only offered when every byte repeats its digit, so no precision is lost
+      ColorFormat::ShortHex => color
+        .bytes()
+        .iter()
+        .map(|byte| (byte % 17 == 0).then(|| format!("{:x}", byte / 17)))
+        .collect::<Option<String>>()
+        .map(|hex| format!("#{hex}"))?,
+      ColorFormat::Rgb => format!(
+        "rgb({} {} {}{alpha})",
+        number(color.r.clamp(0.0, 1.0) * 255.0, 0),
+        number(color.g.clamp(0.0, 1.0) * 255.0, 0),
+        number(color.b.clamp(0.0, 1.0) * 255.0, 0),
+      ),
+      ColorFormat::Hsl => {
+        let (h, s, l) = color.to_hsl();
+        format!(
+          "hsl({} {}% {}%{alpha})",
+          hue_number(h, 1),
+          number(s * 100.0, 1),
+          number(l * 100.0, 1)
+        )
+      }
+      ColorFormat::Oklch => {
+        let (l, c, h) = color.to_oklch();
+        format!(
+          "oklch({}% {} {}{alpha})",
+          number(l * 100.0, 2),
+          number(c, 4),
+          hue_number(h, 2)
+        )
+      }
+    }
+    .pipe(Some)
+  }
+}
+
+impl Color {
+  /// The red, green and blue bytes, followed by alpha when it is not opaque.
+  fn bytes(self) -> Vec<u8> {
+    let byte = |value: f64| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+    let mut bytes = vec![byte(self.r), byte(self.g), byte(self.b)];
+    if self.alpha < 1.0 {
+      bytes.push(byte(self.alpha));
+    }
+    bytes
+  }
+
+  fn from_hsl(h: f64, s: f64, l: f64, alpha: f64) -> Self {
+    let f = |n: f64| {
+      let k = (n + h / 30.0).rem_euclid(12.0);
+      l - s * l.min(1.0 - l) * (k - 3.0).min(9.0 - k).clamp(-1.0, 1.0)
+    };
+    Self {
+      r: f(0.0),
+      g: f(8.0),
+      b: f(4.0),
+      alpha,
+    }
+  }
+
+  fn to_hsl(self) -> (f64, f64, f64) {
+    let (r, g, b) = (self.r, self.g, self.b);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let l = (max + min) / 2.0;
+    let d = max - min;
+    if d == 0.0 {
+      return (0.0, 0.0, l);
+    }
+    let s = if l == 0.0 || l == 1.0 {
+      0.0
+    } else {
+      (max - l) / l.min(1.0 - l)
+    };
+    let h = if max == r {
+      (g - b) / d + if g < b { 6.0 } else { 0.0 }
+    } else if max == g {
+      (b - r) / d + 2.0
+    } else {
+      (r - g) / d + 4.0
+    };
+    (h * 60.0, s, l)
+  }
+
+  fn from_oklch(l: f64, c: f64, h: f64, alpha: f64) -> Self {
+    let (a, b) = (c * (h * PI / 180.0).cos(), c * (h * PI / 180.0).sin());
+    let l_ = (l + 0.3963377774 * a + 0.2158037573 * b).powi(3);
+    let m_ = (l - 0.1055613458 * a - 0.0638541728 * b).powi(3);
+    let s_ = (l - 0.0894841775 * a - 1.2914855480 * b).powi(3);
+    let encode = |c: f64| {
+      if c <= 0.0031308 {
+        12.92 * c
+      } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+      }
+    };
+    Self {
+      r: encode(4.0767416621 * l_ - 3.3077115913 * m_ + 0.2309699292 * s_),
+      g: encode(-1.2684380046 * l_ + 2.6097574011 * m_ - 0.3413193965 * s_),
+      b: encode(-0.0041960863 * l_ - 0.7034186147 * m_ + 1.7076147010 * s_),
+      alpha,
+    }
+  }
+
+  fn to_oklch(self) -> (f64, f64, f64) {
+    let decode = |c: f64| {
+      if c <= 0.04045 {
+        c / 12.92
+      } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+      }
+    };
+    let (r, g, b) = (decode(self.r), decode(self.g), decode(self.b));
+    let l_ = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+    let m_ = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+    let s_ = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+    let l = 0.2104542553 * l_ + 0.7936177850 * m_ - 0.0040720468 * s_;
+    let a = 1.9779984951 * l_ - 2.4285922050 * m_ + 0.4505937099 * s_;
+    let b = 0.0259040371 * l_ + 0.7827717662 * m_ - 0.8086757660 * s_;
+    let c = a.hypot(b);
+    // Achromatic colors have no meaningful hue
+    let h = if c < 1e-4 {
+      0.0
+    } else {
+      b.atan2(a).to_degrees().rem_euclid(360.0)
+    };
+    (l, c, h)
+  }
+}
+
+/// Parses hex, `rgb()`, `rgba()`, `hsl()`, `hsla()` and `oklch()` in both the CSS Color 4
+/// space-separated syntax and the legacy comma syntax.
+fn parse(text: &str) -> Option<Color> {
+  let text = text.trim();
+  if let Some(hex) = text.strip_prefix('#') {
+    return parse_hex(hex);
+  }
+  let (name, args) = text.strip_suffix(')')?.split_once('(')?;
+  let (args, alpha) = match args.split_once('/') {
+    Some((args, alpha)) => (args, Some(alpha.trim())),
+    None => (args, None),
+  };
+  let mut args = args
+    .split(|c: char| c == ',' || c.is_whitespace())
+    .filter(|arg| !arg.is_empty())
+    .collect::<Vec<_>>();
+  let alpha = match (alpha, args.len()) {
+    (Some(alpha), 3) => Some(alpha),
+    (None, 4) => args.pop(),
+    (None, 3) => None,
+    _ => return None,
+  }
+  .map_or(Some(1.0), |alpha| component(alpha, 1.0))?
+  .clamp(0.0, 1.0);
+  let [a, b, c] = args[..] else {
+    return None;
+  };
+  match name.trim().to_ascii_lowercase().as_str() {
+    "rgb" | "rgba" => Some(Color {
+      r: component(a, 255.0)? / 255.0,
+      g: component(b, 255.0)? / 255.0,
+      b: component(c, 255.0)? / 255.0,
+      alpha,
+    }),
+    "hsl" | "hsla" => Some(Color::from_hsl(
+      hue(a)?,
+      component(b, 100.0)? / 100.0,
+      component(c, 100.0)? / 100.0,
+      alpha,
+    )),
+    "oklch" => Some(Color::from_oklch(
+      component(a, 1.0)?,
+      component(b, 0.4)?,
+      hue(c)?,
+      alpha,
+    )),
+    _ => None,
+  }
+}
+
+fn parse_hex(hex: &str) -> Option<Color> {
+  let digits = hex
+    .chars()
+    .map(|c| c.to_digit(16).map(|digit| digit as f64))
+    .collect::<Option<Vec<_>>>()?;
+  let channels = match digits.len() {
+    3 | 4 => digits.iter().map(|digit| digit * 17.0).collect::<Vec<_>>(),
+    6 | 8 => digits
+      .chunks(2)
+      .map(|pair| pair[0] * 16.0 + pair[1])
+      .collect(),
+    _ => return None,
+  };
+  Some(Color {
+    r: channels[0] / 255.0,
+    g: channels[1] / 255.0,
+    b: channels[2] / 255.0,
+    alpha: channels.get(3).map_or(1.0, |alpha| alpha / 255.0),
+  })
+}
+
+/// Parses a number, or a percentage of `full`, with `none` as zero.
+fn component(text: &str, full: f64) -> Option<f64> {
+  if text.eq_ignore_ascii_case("none") {
+    return Some(0.0);
+  }
+  match text.strip_suffix('%') {
+    Some(percent) => percent
+      .parse::<f64>()
+      .ok()
+      .map(|percent| percent / 100.0 * full),
+    None => text.parse().ok(),
+  }
+  .filter(|value: &f64| value.is_finite())
+}
+
+/// Parses a hue in degrees, with optional `deg`, `rad`, `grad` or `turn` units.
+fn hue(text: &str) -> Option<f64> {
+  let text = text.to_ascii_lowercase();
+  [
+    ("deg", 1.0),
+    ("grad", 0.9),
+    ("rad", 180.0 / PI),
+    ("turn", 360.0),
+  ]
+  .into_iter()
+  .find_map(|(unit, factor)| {
+    text
+      .strip_suffix(unit)
+      .and_then(|value| value.parse::<f64>().ok())
+      .map(|value| value * factor)
+  })
+  .or_else(|| component(&text, 1.0))
+}
+
+/// Formats a hue so that values which round up to a full turn wrap to zero.
+fn hue_number(hue: f64, precision: usize) -> String {
+  let scale = 10f64.powi(precision as i32);
+  number(
+    (hue * scale).round().rem_euclid(360.0 * scale) / scale,
+    precision,
+  )
+}
+
+fn number(value: f64, precision: usize) -> String {
+  let formatted = format!("{value:.precision$}");
+  let formatted = if formatted.contains('.') {
+    formatted.trim_end_matches('0').trim_end_matches('.')
+  } else {
+    &formatted
+  };
+  match formatted {
+    "-0" => String::from("0"),
+    formatted => formatted.to_string(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use ropey::Rope;
+  use strum::IntoEnumIterator;
+
+  fn convert(text: &str) -> Vec<String> {
+    ColorFormat::iter()
+      .filter_map(|format| format.transform(Rope::from_str(text).slice(..)))
+      .collect()
+  }
+
+  #[test]
+  fn test_color_conversion() {
+    let red = [
+      "#ff0000",
+      "#f00",
+      "rgb(255 0 0)",
+      "hsl(0 100% 50%)",
+      "oklch(62.8% 0.2577 29.23)",
+    ];
+    red.iter().for_each(|text| assert_eq!(convert(text), red));
+    assert_eq!(convert("#F00"), red);
+    assert_eq!(convert("rgba(255, 0, 0, 1)"), red);
+  }
+
+  #[test]
+  fn test_color_alpha() {
+    assert_eq!(
+      convert("rgba(0, 128, 255, 0.5)"),
+      [
+        "#0080ff80",
+        "rgb(0 128 255 / 0.5)",
+        "hsl(209.9 100% 50% / 0.5)",
+        "oklch(61.52% 0.2108 256.1 / 0.5)",
+      ]
+    );
+    assert_eq!(
+      convert("hsl(0.5turn 50% 50% / 25%)")[0..2],
+      ["#40bfbf40", "rgb(64 191 191 / 0.25)"]
+    );
+  }
+
+  #[test]
+  fn test_color_short_hex() {
+    let short = |text: &str| ColorFormat::ShortHex.transform(Rope::from_str(text).slice(..));
+    assert_eq!(short("rgb(255 255 255 / 0.2)").as_deref(), Some("#fff3"));
+    assert_eq!(short("#aabbcc").as_deref(), Some("#abc"));
+    assert_eq!(short("#aabbcd"), None);
+    assert_eq!(short("rgb(0 128 255)"), None);
+  }
+
+  #[test]
+  fn test_color_rejects() {
+    ["#12", "#ggg", "rgb(1 2)", "cmyk(0 0 0 0)", "rgb(a b c)"]
+      .into_iter()
+      .for_each(|text| assert_eq!(parse(text), None, "{text}"));
+  }
+}
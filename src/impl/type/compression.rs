@@ -0,0 +1,263 @@
+use crate::{
+  r#trait::{CommandMeta, Text, Transform},
+  r#type::{Compress, Config, Decompress},
+};
+use base64::{
+  Engine, alphabet,
+  engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig, general_purpose::STANDARD},
+};
+use flate2::{
+  Compression,
+  read::{DeflateDecoder, MultiGzDecoder, ZlibDecoder},
+  write::{DeflateEncoder, GzEncoder, ZlibEncoder},
+};
+use ropey::RopeSlice;
+use std::io::{Read, Write};
+use tower_lsp::lsp_types::{CodeActionKind, Range};
+
+const LENIENT: GeneralPurposeConfig =
+  GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent);
+const STANDARD_LENIENT: GeneralPurpose = GeneralPurpose::new(&alphabet::STANDARD, LENIENT);
+const URL_SAFE_LENIENT: GeneralPurpose = GeneralPurpose::new(&alphabet::URL_SAFE, LENIENT);
+
+/// CMF says deflate and the header checksum holds.
+fn zlib_header(bytes: &[u8]) -> bool {
+  matches!(bytes, [cmf, flg, ..] if cmf & 0x0f == 8 && (u16::from(*cmf) << 8 | u16::from(*flg)) % 31 == 0)
+}
+
+/// Enough Base64 for the gzip and zlib headers.
+const PROBE: usize = 64;
+
+/// Names the stream format from a decoded prefix of the selection, so offering the action for
+/// gzip and zlib never inflates the whole blob.
+fn header(text: &str) -> Option<&'static str> {
+  let mut encoded = text
+    .chars()
+    .filter(|c| !c.is_ascii_whitespace())
+    .collect::<String>();
+  if !encoded
+    .bytes()
+    .all(|byte| byte.is_ascii_alphanumeric() || b"+/-_=".contains(&byte))
+  {
+    return None;
+  }
+  encoded.truncate(PROBE);
+  let bytes = STANDARD_LENIENT
+    .decode(&encoded)
+    .or_else(|_| URL_SAFE_LENIENT.decode(&encoded))
+    .ok()?;
+  if bytes.starts_with(&[0x1f, 0x8b]) {
+    Some("gzip")
+  } else if zlib_header(&bytes) {
+    Some("zlib")
+  } else {
+    None
+  }
+}
+
+impl Decompress {
+  /// `None` when the text isn't a compressed blob at all, an error when it is one that can't be
+  /// shown. Raw deflate has no header, so it only counts when the whole text inflates to
+  /// non-empty text.
+  fn decompress(&self, text: &str) -> Option<Result<String, String>> {
+    let text = text
+      .chars()
+      .filter(|c| !c.is_ascii_whitespace())
+      .collect::<String>();
+    let format = header(&text).unwrap_or("deflate");
+    let bytes = STANDARD_LENIENT
+      .decode(&text)
+      .or_else(|_| URL_SAFE_LENIENT.decode(&text))
+      .ok()?;
+    let reader: Box<dyn Read> = match format {
+      "gzip" => Box::new(MultiGzDecoder::new(bytes.as_slice())),
+      "zlib" => Box::new(ZlibDecoder::new(bytes.as_slice())),
+      _ => Box::new(DeflateDecoder::new(bytes.as_slice())),
+    };
+    let mut output = Vec::new();
+    let inflated = reader
+      .take(self.limit as u64 + 1)
+      .read_to_end(&mut output)
+      .map_err(|err| format!("Corrupt {format} stream: {err}"))
+      .and_then(|_| {
+        if output.len() > self.limit {
+          Err(format!(
+            "Decompressed {format} output exceeds the {} byte limit",
+            self.limit
+          ))
+        } else {
+          String::from_utf8(output)
+            .map_err(|_| format!("Decompressed {format} output is not UTF-8 text"))
+        }
+      });
+    if format == "deflate" && !inflated.as_ref().is_ok_and(|text| !text.is_empty()) {
+      return None;
+    }
+    Some(inflated)
+  }
+}
+
+impl From<&Config> for Decompress {
+  fn from(config: &Config) -> Self {
+    Self {
+      limit: config.decompress_limit,
+    }
+  }
+}
+
+impl CommandMeta for Decompress {
+  fn command_name(&self) -> &'static str {
+    "text-language-server.decompress"
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    "Decompress Base64 gzip/zlib/deflate"
+  }
+}
+
+impl Transform for Decompress {
+  fn code_action_kind(&self) -> Vec<CodeActionKind> {
+    vec![CodeActionKind::REFACTOR_REWRITE, CodeActionKind::SOURCE]
+  }
+
+  fn code_action_condition(&self, source: RopeSlice, range: Range) -> bool {
+    let text = source.slice(source.range(range)).to_string();
+    header(&text).is_some() || self.decompress(&text).is_some()
+  }
+
+  fn transform(&self, text: RopeSlice) -> Option<String> {
+    self.decompress(&text.to_string())?.ok()
+  }
+
+  fn validate(&self, source: RopeSlice, range: Range) -> Result<(), String> {
+    self
+      .decompress(&source.slice(source.range(range)).to_string())
+      .unwrap_or_else(|| Err("Not a Base64 gzip, zlib or deflate stream".to_string()))
+      .map(|_| ())
+  }
+}
+
+impl Compress {
+  fn compress(&self, text: &str) -> std::io::Result<Vec<u8>> {
+    match self {
+      Compress::Gzip => {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(text.as_bytes())?;
+        encoder.finish()
+      }
+      Compress::Zlib => {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(text.as_bytes())?;
+        encoder.finish()
+      }
+      Compress::Deflate => {
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(text.as_bytes())?;
+        encoder.finish()
+      }
+    }
+  }
+}
+
+impl CommandMeta for Compress {
+  fn command_name(&self) -> &'static str {
+    match self {
+      Compress::Gzip => "text-language-server.compress-gzip",
+      Compress::Zlib => "text-language-server.compress-zlib",
+      Compress::Deflate => "text-language-server.compress-deflate",
+    }
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    match self {
+      Compress::Gzip => "Compress to Base64 gzip",
+      Compress::Zlib => "Compress to Base64 zlib",
+      Compress::Deflate => "Compress to Base64 deflate",
+    }
+  }
+}
+
+impl Transform for Compress {
+  fn code_action_kind(&self) -> Vec<CodeActionKind> {
+    vec![CodeActionKind::REFACTOR_REWRITE, CodeActionKind::SOURCE]
+  }
+
+  fn code_action_condition(&self, _: RopeSlice, range: Range) -> bool {
+    range.start != range.end
+  }
+
+  fn transform(&self, text: RopeSlice) -> Option<String> {
+    self
+      .compress(&text.to_string())
+      .ok()
+      .map(|bytes| STANDARD.encode(bytes))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use ropey::Rope;
+  use strum::IntoEnumIterator;
+
+  fn apply(transform: &impl Transform, text: &str) -> Option<String> {
+    transform.transform(Rope::from_str(text).slice(..))
+  }
+
+  #[test]
+  fn test_compress_round_trip() {
+    let decompress = Decompress { limit: 1024 };
+    for compress in Compress::iter() {
+      let encoded = apply(&compress, "apiVersion: v1\nkind: Secret\n").unwrap();
+      assert_eq!(
+        apply(&decompress, &encoded).as_deref(),
+        Some("apiVersion: v1\nkind: Secret\n")
+      );
+    }
+    assert_eq!(
+      apply(&decompress, "H4sIAAAAAAACA8tIzcnJBwCGphA2BQAAAA=="),
+      Some("hello".to_string())
+    );
+    assert_eq!(apply(&decompress, "aGVsbG8="), None);
+  }
+
+  #[test]
+  fn test_header() {
+    let decompress = Decompress { limit: 1 << 16 };
+    for (compress, format) in Compress::iter().zip([Some("gzip"), Some("zlib"), None]) {
+      let rope = Rope::from_str(&apply(&compress, &"lorem ipsum ".repeat(1024)).unwrap());
+      let source = rope.slice(..);
+      assert_eq!(header(&rope.to_string()), format);
+      assert!(decompress.code_action_condition(source, source.range_full()));
+    }
+    for word in ["Some", "info", "item", "init", "42"] {
+      let rope = Rope::from_str(word);
+      let source = rope.slice(..);
+      assert!(!decompress.code_action_condition(source, source.range_full()));
+      assert!(decompress.validate(source, source.range_full()).is_err());
+    }
+    // Only the header is looked at when offering the action, so a corrupt body shows up on execute.
+    let rope = Rope::from_str("H4sIAAAAAAAC////////");
+    let source = rope.slice(..);
+    assert!(decompress.code_action_condition(source, source.range_full()));
+    assert!(decompress.validate(source, source.range_full()).is_err());
+  }
+
+  #[test]
+  fn test_decompress_limit() {
+    let rope = Rope::from_str(&apply(&Compress::Gzip, &"a".repeat(4096)).unwrap());
+    let source = rope.slice(..);
+    let decompress = Decompress { limit: 1024 };
+    assert!(decompress.code_action_condition(source, source.range_full()));
+    assert_eq!(decompress.transform(source), None);
+    assert_eq!(
+      decompress.validate(source, source.range_full()),
+      Err("Decompressed gzip output exceeds the 1024 byte limit".to_string())
+    );
+    assert!(
+      Decompress { limit: 4096 }
+        .validate(source, source.range_full())
+        .is_ok()
+    );
+  }
+}
@@ -0,0 +1,26 @@
+use crate::r#type::{Config, DigestEncoding, DigestInput, LineEnding, SqlKeywordCase};
+
+impl Default for Config {
+  fn default() -> Self {
+    Self {
+      cron_runs: 5,
+      cron_zone: String::from("UTC"),
+      decompress_limit: 16 * 1024 * 1024,
+      digest_encoding: DigestEncoding::Hex,
+      digest_input: DigestInput::Raw,
+      indent_width: 2,
+      line_ending: LineEnding::Lf,
+      password: Default::default(),
+      random_int: Default::default(),
+      random_token_bytes: 32,
+      reflow_width: 80,
+      slug_max_length: None,
+      slug_separator: String::from("-"),
+      sql_keyword_case: SqlKeywordCase::Upper,
+      tab_width: 4,
+      timestamp_format: String::from("rfc3339"),
+      timestamp_zone: String::from("UTC"),
+      typography_mapping: Default::default(),
+    }
+  }
+}
@@ -0,0 +1,281 @@
+use crate::{
+  r#trait::{CommandMeta, Text, Transform},
+  r#type::{Config, ExplainCron},
+};
+use chrono::{SecondsFormat, Utc};
+use chrono_tz::Tz;
+use croner::{
+  Cron,
+  parser::{CronParser, Seconds, Year},
+};
+use ropey::{LineType, RopeSlice};
+use tower_lsp::lsp_types::{CodeActionKind, Position, Range, TextEdit};
+
+/// `?`, `#`, and `L` or `W` on their own or after a day number. Names such as `WED` or `JUL`
+/// don't count.
+fn quartz_special(field: &str) -> bool {
+  field.contains(['?', '#'])
+    || field.split(',').any(|part| {
+      let part = part.to_ascii_uppercase();
+      let day = part.trim_end_matches(['L', 'W']);
+      matches!(part.as_str(), "L" | "LW")
+        || part.starts_with("L-")
+        || (day.len() < part.len() && !day.is_empty() && day.bytes().all(|b| b.is_ascii_digit()))
+    })
+}
+
+/// Five fields are classic cron and six or seven lead with seconds. Quartz, which numbers weekdays
+/// from `1` for Sunday, is told apart by a trailing year or one of its `?`, `L`, `W` or `#`
+/// specials; six fields without them are the Spring, node-cron and robfig form with `0` for
+/// Sunday.
+fn parse(expression: &str) -> Option<Cron> {
+  let fields = expression.split_whitespace().count();
+  if !(5..=7).contains(&fields) {
+    return None;
+  }
+  CronParser::builder()
+    .seconds(if fields == 5 {
+      Seconds::Disallowed
+    } else {
+      Seconds::Required
+    })
+    .year(if fields == 7 {
+      Year::Required
+    } else {
+      Year::Disallowed
+    })
+    .alternative_weekdays(fields == 7 || expression.split_whitespace().any(quartz_special))
+    .build()
+    .parse(expression)
+    .ok()
+}
+
+const COMMENT_MARKERS: [&str; 4] = ["#", "//", "--", ";"];
+
+/// The marker a line's comment starts with, whether the whole line or only its tail is a comment.
+/// Crontabs, YAML and TOML all use `#`, so that is the fallback.
+fn comment_marker(line: &str) -> &'static str {
+  let trimmed = line.trim_start();
+  COMMENT_MARKERS
+    .into_iter()
+    .find(|marker| trimmed.starts_with(marker))
+    .or_else(|| {
+      COMMENT_MARKERS
+        .into_iter()
+        .find(|marker| line.contains(&format!(" {marker} ")))
+    })
+    .unwrap_or("#")
+}
+
+impl ExplainCron {
+  fn zone(&self) -> Result<Tz, String> {
+    self
+      .zone
+      .parse::<Tz>()
+      .map_err(|_| format!("Unknown time zone {:?}", self.zone))
+  }
+
+  /// The description followed by one line per upcoming fire time.
+  pub fn explain(&self, expression: &str) -> Option<String> {
+    let expression = expression.trim();
+    let cron = parse(expression)?;
+    let zone = self.zone().ok()?;
+    let mut explanation = format!("{}\nNext {} runs ({zone}):", cron.describe(), self.runs);
+    for run in cron
+      .iter_after(self.now.with_timezone(&zone))
+      .take(self.runs)
+    {
+      explanation.push_str("\n  ");
+      explanation.push_str(&run.to_rfc3339_opts(SecondsFormat::Secs, zone == Tz::UTC));
+    }
+    Some(explanation)
+  }
+
+  /// A cron expression under the cursor, either quoted, as in CI YAML, or after a `cron:` key.
+  pub fn hover(&self, line: &str, offset: usize) -> Option<String> {
+    let mut quoted = None;
+    let mut start = None;
+    for (idx, c) in line.char_indices() {
+      match start {
+        Some((quote, open)) if c == quote => {
+          if (open..=idx).contains(&offset) {
+            quoted = Some(&line[open + 1..idx]);
+          }
+          start = None;
+        }
+        None if c == '"' || c == '\'' => start = Some((c, idx)),
+        _ => {}
+      }
+    }
+    let expression = quoted.or_else(|| {
+      line
+        .split_once("cron:")
+        .map(|(_, expression)| expression.split(" #").next().unwrap_or(expression))
+    })?;
+    self.explain(expression)
+  }
+}
+
+impl From<&Config> for ExplainCron {
+  fn from(config: &Config) -> Self {
+    Self {
+      zone: config.cron_zone.clone(),
+      runs: config.cron_runs,
+      now: Utc::now(),
+    }
+  }
+}
+
+impl CommandMeta for ExplainCron {
+  fn command_name(&self) -> &'static str {
+    "text-language-server.explain-cron"
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    "Explain cron expression"
+  }
+}
+
+impl Transform for ExplainCron {
+  fn code_action_kind(&self) -> Vec<CodeActionKind> {
+    vec![CodeActionKind::QUICKFIX, CodeActionKind::SOURCE]
+  }
+
+  fn code_action_condition(&self, source: RopeSlice, range: Range) -> bool {
+    parse(&source.slice(source.range(range)).to_string()).is_some()
+  }
+
+  fn transform(&self, text: RopeSlice) -> Option<String> {
+    self.explain(&text.to_string())
+  }
+
+  fn validate(&self, _: RopeSlice, _: Range) -> Result<(), String> {
+    self.zone().map(|_| ())
+  }
+
+  /// The explanation goes on the lines below the one the selection ends on, commented out and
+  /// indented like that line, so a selection inside a YAML value or crontab entry is left intact.
+  fn text_edits(&self, source: RopeSlice, range: Range) -> Option<Vec<TextEdit>> {
+    let explanation = self.transform(source.slice(source.range(range)))?;
+    let line = source
+      .line(range.end.line as usize, LineType::LF_CR)
+      .to_string();
+    let line = line.trim_end_matches(['\r', '\n']);
+    let indent = &line[..line.len() - line.trim_start().len()];
+    let marker = comment_marker(line);
+    let end = Position::new(range.end.line, line.encode_utf16().count() as u32);
+    Some(vec![TextEdit {
+      range: Range::new(end, end),
+      new_text: explanation
+        .lines()
+        .map(|explanation| format!("\n{indent}{marker} {explanation}"))
+        .collect(),
+    }])
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use chrono::{DateTime, TimeZone};
+  use ropey::Rope;
+
+  fn explain_cron(zone: &str) -> ExplainCron {
+    ExplainCron {
+      zone: zone.to_string(),
+      runs: 3,
+      now: Utc.with_ymd_and_hms(2025, 3, 9, 20, 5, 26).unwrap(),
+    }
+  }
+
+  fn runs(explanation: &str) -> Vec<&str> {
+    explanation.lines().skip(2).map(str::trim).collect()
+  }
+
+  #[test]
+  fn test_explain_cron() {
+    let explanation = explain_cron("UTC")
+      .explain("*/15 9-17 * * MON-FRI")
+      .unwrap();
+    assert!(explanation.contains("\nNext 3 runs (UTC):\n"));
+    assert_eq!(
+      runs(&explanation),
+      vec![
+        "2025-03-10T09:00:00Z",
+        "2025-03-10T09:15:00Z",
+        "2025-03-10T09:30:00Z"
+      ]
+    );
+    assert_eq!(
+      runs(
+        &explain_cron("America/New_York")
+          .explain("30 0 12 * * ?")
+          .unwrap()
+      ),
+      vec![
+        "2025-03-10T12:00:30-04:00",
+        "2025-03-11T12:00:30-04:00",
+        "2025-03-12T12:00:30-04:00"
+      ]
+    );
+    // Quartz numbers weekdays from Sunday, so `6` is Friday.
+    assert_eq!(
+      runs(&explain_cron("UTC").explain("0 0 8 ? * 6 2025").unwrap())[0]
+        .parse::<DateTime<Utc>>()
+        .unwrap(),
+      Utc.with_ymd_and_hms(2025, 3, 14, 8, 0, 0).unwrap()
+    );
+    // Six fields without Quartz specials count weekdays from `0` for Sunday, so `1-5` is Monday
+    // to Friday.
+    assert_eq!(
+      runs(&explain_cron("UTC").explain("0 0 8 * * 1-5").unwrap())
+        .into_iter()
+        .map(|run| run.parse::<DateTime<Utc>>().unwrap())
+        .collect::<Vec<_>>(),
+      [10, 11, 12]
+        .map(|day| Utc.with_ymd_and_hms(2025, 3, day, 8, 0, 0).unwrap())
+        .to_vec()
+    );
+    assert!(quartz_special("6L") && quartz_special("15W") && quartz_special("L-2"));
+    assert!(!quartz_special("MON-WED") && !quartz_special("JUL"));
+    assert_eq!(explain_cron("UTC").explain("* * * *"), None);
+    assert_eq!(explain_cron("UTC").explain("61 * * * *"), None);
+  }
+
+  #[test]
+  fn test_explain_cron_edits_and_hover() {
+    let rope = Rope::from_str("on:\n  schedule:\n    - cron: '0 3 * * 1'\n");
+    let source = rope.slice(..);
+    let range = Range::new(Position::new(2, 13), Position::new(2, 22));
+    let edits = explain_cron("UTC").text_edits(source, range).unwrap();
+    assert_eq!(
+      edits[0].range,
+      Range::new(Position::new(2, 23), Position::new(2, 23))
+    );
+    assert!(edits[0].new_text.starts_with("\n    # At "));
+    assert!(
+      edits[0]
+        .new_text
+        .ends_with("\n    #   2025-03-24T03:00:00Z")
+    );
+    assert!(
+      edits[0]
+        .new_text
+        .lines()
+        .skip(1)
+        .all(|line| line.starts_with("    # "))
+    );
+    assert_eq!(comment_marker("  -- 0 3 * * 1"), "--");
+    assert_eq!(comment_marker("schedule(\"0 3 * * 1\"); // weekly"), "//");
+    let line = "    - cron: '0 3 * * 1' # weekly";
+    assert_eq!(
+      explain_cron("UTC").hover(line, 16),
+      explain_cron("UTC").explain("0 3 * * 1")
+    );
+    assert_eq!(
+      explain_cron("UTC").hover("schedule = cron: 0 3 * * 1 # weekly", 0),
+      explain_cron("UTC").explain("0 3 * * 1")
+    );
+    assert_eq!(explain_cron("UTC").hover("name: 'nightly build'", 8), None);
+  }
+}
@@ -0,0 +1,118 @@
+use crate::{
+  r#trait::{CommandMeta, Text, Transform},
+  r#type::{DecodeJwt, EpochToUTC},
+};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use chrono::{DateTime, Utc};
+use ropey::RopeSlice;
+use serde_json::{Map, Value, from_slice, to_string_pretty};
+use tower_lsp::lsp_types::{CodeActionKind, Range};
+
+const TIME_CLAIMS: [&str; 3] = ["exp", "iat", "nbf"];
+
+impl CommandMeta for DecodeJwt {
+  fn command_name(&self) -> &'static str {
+    "text-language-server.decode-jwt"
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    "Decode JWT"
+  }
+}
+
+impl Transform for DecodeJwt {
+  fn code_action_kind(&self) -> Vec<CodeActionKind> {
+    vec![CodeActionKind::QUICKFIX, CodeActionKind::SOURCE]
+  }
+
+  fn code_action_condition(&self, source: RopeSlice, range: Range) -> bool {
+    decode(&source.slice(source.range(range)).to_string(), Utc::now()).is_some()
+  }
+
+  fn transform(&self, text: RopeSlice) -> Option<String> {
+    decode(&text.to_string(), Utc::now()).and_then(|decoded| to_string_pretty(&decoded).ok())
+  }
+}
+
+fn decode(token: &str, now: DateTime<Utc>) -> Option<Value> {
+  let token = token.trim();
+  let token = token.strip_prefix("Bearer ").unwrap_or(token);
+  let [header, payload, signature] = token.split('.').collect::<Vec<_>>().try_into().ok()?;
+  let header = segment(header).filter(Value::is_object)?;
+  let payload = segment(payload)?;
+  let mut decoded = Map::new();
+  let times = TIME_CLAIMS
+    .into_iter()
+    .filter_map(|claim| {
+      payload
+        .get(claim)
+        .and_then(Value::as_i64)
+        .and_then(EpochToUTC::format)
+        .map(|utc| (claim.to_string(), Value::String(utc)))
+    })
+    .collect::<Map<_, _>>();
+  let expired = payload
+    .get("exp")
+    .and_then(Value::as_i64)
+    .map(|exp| exp <= now.timestamp());
+  decoded.insert("header".to_string(), header);
+  decoded.insert("payload".to_string(), payload);
+  if !times.is_empty() {
+    decoded.insert("times".to_string(), Value::Object(times));
+  }
+  if let Some(expired) = expired {
+    decoded.insert("expired".to_string(), Value::Bool(expired));
+  }
+  decoded.insert(
+    "signature".to_string(),
+    Value::String(signature.to_string()),
+  );
+  Some(Value::Object(decoded))
+}
+
+fn segment(segment: &str) -> Option<Value> {
+  URL_SAFE_NO_PAD
+    .decode(segment.trim_end_matches('='))
+    .ok()
+    .and_then(|bytes| from_slice(&bytes).ok())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::json;
+
+  fn token(header: Value, payload: Value) -> String {
+    [header, payload]
+      .map(|value| URL_SAFE_NO_PAD.encode(value.to_string()))
+      .join(".")
+      + ".c2lnbmF0dXJl"
+  }
+
+  #[test]
+  fn test_decode() {
+    let token = token(
+      json!({ "alg": "HS256", "typ": "JWT" }),
+      json!({ "sub": "1234567890", "iat": 1516239022, "exp": 1741550726 }),
+    );
+    let now = DateTime::from_timestamp(1741550000, 0).unwrap();
+    assert_eq!(
+      decode(&format!("Bearer {token}\n"), now),
+      Some(json!({
+        "header": { "alg": "HS256", "typ": "JWT" },
+        "payload": { "sub": "1234567890", "iat": 1516239022, "exp": 1741550726 },
+        "times": { "exp": "2025-03-09T20:05:26Z", "iat": "2018-01-18T01:30:22Z" },
+        "expired": false,
+        "signature": "c2lnbmF0dXJl",
+      }))
+    );
+    let now = DateTime::from_timestamp(1741550726, 0).unwrap();
+    assert_eq!(decode(&token, now).unwrap()["expired"], json!(true));
+  }
+
+  #[test]
+  fn test_decode_rejects_non_jwt() {
+    assert_eq!(decode("a.b.c", Utc::now()), None);
+    assert_eq!(decode("not a token", Utc::now()), None);
+  }
+}
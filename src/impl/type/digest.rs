@@ -0,0 +1,192 @@
+use crate::{
+  r#trait::{CommandMeta, Transform},
+  r#type::{Append, Config, Digest, DigestAlgorithm, DigestEncoding, DigestInput},
+};
+use base64::{Engine, engine::general_purpose::STANDARD};
+use md5::Md5;
+use ropey::RopeSlice;
+use sha1::Sha1;
+use sha2::{Digest as _, Sha256};
+use std::fmt::Write;
+use tap::prelude::*;
+use tower_lsp::lsp_types::{CodeActionKind, Range};
+
+impl Digest {
+  pub fn new(algorithm: DigestAlgorithm, config: &Config) -> Self {
+    Self {
+      algorithm,
+      encoding: config.digest_encoding,
+      input: config.digest_input,
+    }
+  }
+
+  fn digest(&self, bytes: &[u8]) -> Vec<u8> {
+    match self.algorithm {
+      DigestAlgorithm::Sha256 => Sha256::digest(bytes).to_vec(),
+      DigestAlgorithm::Sha1 => Sha1::digest(bytes).to_vec(),
+      DigestAlgorithm::Md5 => Md5::digest(bytes).to_vec(),
+      DigestAlgorithm::Crc32 => crc32fast::hash(bytes).to_be_bytes().to_vec(),
+      DigestAlgorithm::Blake3 => blake3::hash(bytes).as_bytes().to_vec(),
+    }
+  }
+}
+
+impl CommandMeta for Digest {
+  fn command_name(&self) -> &'static str {
+    match self.algorithm {
+      DigestAlgorithm::Sha256 => "text-language-server.sha256",
+      DigestAlgorithm::Sha1 => "text-language-server.sha1",
+      DigestAlgorithm::Md5 => "text-language-server.md5",
+      DigestAlgorithm::Crc32 => "text-language-server.crc32",
+      DigestAlgorithm::Blake3 => "text-language-server.blake3",
+    }
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    match (self.algorithm, self.encoding) {
+      (DigestAlgorithm::Sha256, DigestEncoding::Hex) => "SHA-256 (hex)",
+      (DigestAlgorithm::Sha256, DigestEncoding::Base64) => "SHA-256 (Base64)",
+      (DigestAlgorithm::Sha1, DigestEncoding::Hex) => "SHA-1 (hex)",
+      (DigestAlgorithm::Sha1, DigestEncoding::Base64) => "SHA-1 (Base64)",
+      (DigestAlgorithm::Md5, DigestEncoding::Hex) => "MD5 (hex)",
+      (DigestAlgorithm::Md5, DigestEncoding::Base64) => "MD5 (Base64)",
+      (DigestAlgorithm::Crc32, DigestEncoding::Hex) => "CRC32 (hex)",
+      (DigestAlgorithm::Crc32, DigestEncoding::Base64) => "CRC32 (Base64)",
+      (DigestAlgorithm::Blake3, DigestEncoding::Hex) => "BLAKE3 (hex)",
+      (DigestAlgorithm::Blake3, DigestEncoding::Base64) => "BLAKE3 (Base64)",
+    }
+  }
+}
+
+impl CommandMeta for Append<Digest> {
+  fn command_name(&self) -> &'static str {
+    match self.0.algorithm {
+      DigestAlgorithm::Sha256 => "text-language-server.append-sha256",
+      DigestAlgorithm::Sha1 => "text-language-server.append-sha1",
+      DigestAlgorithm::Md5 => "text-language-server.append-md5",
+      DigestAlgorithm::Crc32 => "text-language-server.append-crc32",
+      DigestAlgorithm::Blake3 => "text-language-server.append-blake3",
+    }
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    match (self.0.algorithm, self.0.encoding) {
+      (DigestAlgorithm::Sha256, DigestEncoding::Hex) => "Append SHA-256 (hex)",
+      (DigestAlgorithm::Sha256, DigestEncoding::Base64) => "Append SHA-256 (Base64)",
+      (DigestAlgorithm::Sha1, DigestEncoding::Hex) => "Append SHA-1 (hex)",
+      (DigestAlgorithm::Sha1, DigestEncoding::Base64) => "Append SHA-1 (Base64)",
+      (DigestAlgorithm::Md5, DigestEncoding::Hex) => "Append MD5 (hex)",
+      (DigestAlgorithm::Md5, DigestEncoding::Base64) => "Append MD5 (Base64)",
+      (DigestAlgorithm::Crc32, DigestEncoding::Hex) => "Append CRC32 (hex)",
+      (DigestAlgorithm::Crc32, DigestEncoding::Base64) => "Append CRC32 (Base64)",
+      (DigestAlgorithm::Blake3, DigestEncoding::Hex) => "Append BLAKE3 (hex)",
+      (DigestAlgorithm::Blake3, DigestEncoding::Base64) => "Append BLAKE3 (Base64)",
+    }
+  }
+}
+
+impl Transform for Digest {
+  fn code_action_kind(&self) -> Vec<CodeActionKind> {
+    vec![CodeActionKind::REFACTOR_REWRITE, CodeActionKind::SOURCE]
+  }
+
+  fn code_action_condition(&self, _: RopeSlice, range: Range) -> bool {
+    range.start != range.end
+  }
+
+  fn transform(&self, text: RopeSlice) -> Option<String> {
+    let text = text.to_string();
+    let bytes = match self.input {
+      DigestInput::Utf8Lf => text.replace("\r\n", "\n").replace('\r', "\n"),
+      DigestInput::Raw => text,
+    };
+    let digest = self.digest(bytes.as_bytes());
+    match self.encoding {
+      DigestEncoding::Hex => digest.iter().fold(String::new(), |mut hex, byte| {
+        let _ = write!(hex, "{byte:02x}");
+        hex
+      }),
+      DigestEncoding::Base64 => STANDARD.encode(digest),
+    }
+    .pipe(Some)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use ropey::Rope;
+  use strum::IntoEnumIterator;
+
+  fn digest(
+    text: &str,
+    algorithm: DigestAlgorithm,
+    encoding: DigestEncoding,
+    input: DigestInput,
+  ) -> String {
+    Digest {
+      algorithm,
+      encoding,
+      input,
+    }
+    .transform(Rope::from_str(text).slice(..))
+    .unwrap()
+  }
+
+  #[test]
+  fn test_digest_hex() {
+    assert_eq!(
+      DigestAlgorithm::iter()
+        .map(|algorithm| digest(
+          "abc\r\n",
+          algorithm,
+          DigestEncoding::Hex,
+          DigestInput::Utf8Lf
+        ))
+        .take(4)
+        .collect::<Vec<_>>(),
+      [
+        "edeaaff3f1774ad2888673770c6d64097e391bc362d7d6fb34982ddf0efd18cb",
+        "03cfd743661f07975fa2f1220c5194cbaff48451",
+        "0bee89b07a248e27c83fc3d5951213c1",
+        "4788814e",
+      ]
+    );
+    assert_eq!(
+      digest(
+        "",
+        DigestAlgorithm::Blake3,
+        DigestEncoding::Hex,
+        DigestInput::Raw
+      ),
+      "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262"
+    );
+  }
+
+  #[test]
+  fn test_digest_input_and_encoding() {
+    assert_ne!(
+      digest(
+        "abc\r\n",
+        DigestAlgorithm::Sha256,
+        DigestEncoding::Hex,
+        DigestInput::Raw
+      ),
+      digest(
+        "abc\r\n",
+        DigestAlgorithm::Sha256,
+        DigestEncoding::Hex,
+        DigestInput::Utf8Lf
+      )
+    );
+    assert_eq!(
+      digest(
+        "abc\n",
+        DigestAlgorithm::Crc32,
+        DigestEncoding::Base64,
+        DigestInput::Raw
+      ),
+      "R4iBTg=="
+    );
+  }
+}
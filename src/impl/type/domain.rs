@@ -0,0 +1,143 @@
+use crate::{
+  r#trait::{CommandMeta, Text, Transform},
+  r#type::DomainFormat,
+};
+use regex::Regex;
+use ropey::RopeSlice;
+use std::{collections::HashSet, sync::LazyLock};
+use tower_lsp::lsp_types::{CodeActionKind, Range};
+
+static DOMAIN: LazyLock<Regex> = LazyLock::new(|| {
+  let label = r"[\p{L}\p{M}\p{N}](?:[\p{L}\p{M}\p{N}-]*[\p{L}\p{M}\p{N}])?";
+  Regex::new(&format!(r"{label}(?:\.{label})+")).unwrap()
+});
+
+static TLDS: LazyLock<HashSet<&str>> = LazyLock::new(|| {
+  include_str!("tlds.txt")
+    .lines()
+    .filter(|line| !line.starts_with("//"))
+    .collect()
+});
+
+/// Every label fits in 63 bytes once encoded and the last one is a delegated top-level domain, so
+/// file names such as `résumé.pdf` aren't taken for domains.
+fn is_domain(domain: &str) -> bool {
+  let tld = domain.rsplit('.').next().unwrap_or_default();
+  idna::domain_to_ascii(domain).is_ok_and(|ascii| ascii.split('.').all(|label| label.len() <= 63))
+    && TLDS.contains(idna::domain_to_unicode(tld).0.as_str())
+}
+
+impl DomainFormat {
+  fn label(&self, label: &str) -> Option<String> {
+    match self {
+      DomainFormat::Ascii if !label.is_ascii() => idna::domain_to_ascii(label).ok(),
+      DomainFormat::Unicode
+        if label
+          .get(..4)
+          .is_some_and(|prefix| prefix.eq_ignore_ascii_case("xn--")) =>
+      {
+        match idna::domain_to_unicode(label) {
+          (unicode, Ok(())) => Some(unicode),
+          _ => None,
+        }
+      }
+      _ => None,
+    }
+  }
+
+  /// Converts every domain in the text label by label. Labels that are already in the target
+  /// form, or that fail to convert, are kept as written. Email local parts and path segments
+  /// look like domains but aren't, so matches directly before `@` or after a lone `/` are skipped,
+  /// as are file names and anything else that doesn't end in a known top-level domain.
+  fn convert(&self, text: &str) -> String {
+    let mut converted = String::with_capacity(text.len());
+    let mut last = 0;
+    for domain in DOMAIN.find_iter(text) {
+      let before = &text[..domain.start()];
+      if text[domain.end()..].starts_with('@')
+        || (before.ends_with('/') && !before.ends_with("//"))
+        || !is_domain(domain.as_str())
+      {
+        continue;
+      }
+      converted.push_str(&text[last..domain.start()]);
+      converted.push_str(
+        &domain
+          .as_str()
+          .split('.')
+          .map(|label| self.label(label).unwrap_or_else(|| label.to_string()))
+          .collect::<Vec<_>>()
+          .join("."),
+      );
+      last = domain.end();
+    }
+    converted.push_str(&text[last..]);
+    converted
+  }
+}
+
+impl CommandMeta for DomainFormat {
+  fn command_name(&self) -> &'static str {
+    match self {
+      DomainFormat::Ascii => "text-language-server.domain-to-ascii",
+      DomainFormat::Unicode => "text-language-server.domain-to-unicode",
+    }
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    match self {
+      DomainFormat::Ascii => "Domain to ASCII (Punycode)",
+      DomainFormat::Unicode => "Domain to Unicode",
+    }
+  }
+}
+
+impl Transform for DomainFormat {
+  fn code_action_kind(&self) -> Vec<CodeActionKind> {
+    vec![CodeActionKind::QUICKFIX, CodeActionKind::SOURCE]
+  }
+
+  fn code_action_condition(&self, source: RopeSlice, range: Range) -> bool {
+    let text = source.slice(source.range(range)).to_string();
+    self.convert(&text) != text
+  }
+
+  fn transform(&self, text: RopeSlice) -> Option<String> {
+    Some(self.convert(&text.to_string()))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_domain_to_ascii() {
+    assert_eq!(
+      DomainFormat::Ascii
+        .convert("mail josé@bücher.example.de or see https://münchen.de/über.html"),
+      "mail josé@xn--bcher-kva.example.de or see https://xn--mnchen-3ya.de/über.html"
+    );
+    assert_eq!(
+      DomainFormat::Ascii.convert("www.例え.みんな"),
+      "www.xn--r8jz45g.xn--q9jyb4c"
+    );
+    assert_eq!(
+      DomainFormat::Ascii.convert("attach résumé.pdf to mail.ü.example"),
+      "attach résumé.pdf to mail.ü.example"
+    );
+    assert_eq!(DomainFormat::Ascii.convert("plain text."), "plain text.");
+  }
+
+  #[test]
+  fn test_domain_to_unicode() {
+    assert_eq!(
+      DomainFormat::Unicode.convert("CN=xn--bcher-kva.example.de, admin@XN--MNCHEN-3YA.de"),
+      "CN=bücher.example.de, admin@münchen.de"
+    );
+    assert_eq!(
+      DomainFormat::Unicode.convert("xn--invalid-.example"),
+      "xn--invalid-.example"
+    );
+  }
+}
@@ -0,0 +1,273 @@
+use crate::{
+  r#trait::{CommandMeta, Text, Transform},
+  r#type::DurationFormat,
+};
+use ropey::RopeSlice;
+use std::fmt::Write;
+use tower_lsp::lsp_types::{CodeActionKind, Range};
+
+const NANOS_PER_SEC: i128 = 1_000_000_000;
+
+/// Go and compact units in nanoseconds, longest suffix first so `ms` is not read as `m`.
+const UNITS: [(&str, i128); 10] = [
+  ("ns", 1),
+  ("us", 1_000),
+  ("µs", 1_000),
+  ("μs", 1_000),
+  ("ms", 1_000_000),
+  ("s", NANOS_PER_SEC),
+  ("m", 60 * NANOS_PER_SEC),
+  ("h", 3_600 * NANOS_PER_SEC),
+  ("d", 86_400 * NANOS_PER_SEC),
+  ("w", 604_800 * NANOS_PER_SEC),
+];
+
+impl CommandMeta for DurationFormat {
+  fn command_name(&self) -> &'static str {
+    match self {
+      DurationFormat::Seconds => "text-language-server.duration-seconds",
+      DurationFormat::Iso8601 => "text-language-server.duration-iso8601",
+      DurationFormat::Go => "text-language-server.duration-go",
+      DurationFormat::Compact => "text-language-server.duration-compact",
+    }
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    match self {
+      DurationFormat::Seconds => "Duration to seconds",
+      DurationFormat::Iso8601 => "Duration to ISO 8601",
+      DurationFormat::Go => "Duration to Go",
+      DurationFormat::Compact => "Duration to compact",
+    }
+  }
+}
+
+impl Transform for DurationFormat {
+  fn code_action_kind(&self) -> Vec<CodeActionKind> {
+    vec![CodeActionKind::QUICKFIX, CodeActionKind::SOURCE]
+  }
+
+  fn code_action_condition(&self, source: RopeSlice, range: Range) -> bool {
+    let text = source.slice(source.range(range));
+    self
+      .transform(text)
+      .is_some_and(|result| result != text.to_string().trim())
+  }
+
+  fn transform(&self, text: RopeSlice) -> Option<String> {
+    let nanos = parse(&text.to_string())?;
+    let sign = if nanos < 0 { "-" } else { "" };
+    let nanos = nanos.abs();
+    let formatted = match self {
+      DurationFormat::Seconds => decimal(nanos, NANOS_PER_SEC),
+      DurationFormat::Iso8601 => iso8601(nanos),
+      DurationFormat::Go => go(nanos),
+      DurationFormat::Compact => compact(nanos),
+    };
+    Some(format!("{sign}{formatted}"))
+  }
+}
+
+/// Parses seconds, ISO 8601 durations without years or months, and Go or compact unit strings.
+fn parse(text: &str) -> Option<i128> {
+  let text = text.trim();
+  let (sign, text) = match text.strip_prefix('-') {
+    Some(text) => (-1, text),
+    None => (1, text.strip_prefix('+').unwrap_or(text)),
+  };
+  let nanos = if text.starts_with(['P', 'p']) {
+    parse_iso8601(&text[1..])?
+  } else if text.chars().all(|c| c.is_ascii_digit() || c == '.') {
+    scale(text, NANOS_PER_SEC)?
+  } else {
+    parse_units(text)?
+  };
+  Some(sign * nanos)
+}
+
+fn parse_iso8601(text: &str) -> Option<i128> {
+  let (date, time) = text
+    .split_once(['T', 't'])
+    .map_or((text, None), |(date, time)| (date, Some(time)));
+  if date.is_empty() && time.is_none_or(str::is_empty) {
+    return None;
+  }
+  let date = components(date, &[('W', 604_800), ('D', 86_400)])?;
+  let time = time.map_or(Some(0), |time| {
+    components(time, &[('H', 3_600), ('M', 60), ('S', 1)])
+  })?;
+  date.checked_add(time)
+}
+
+/// Sums `<number><designator>` pairs, where designators must appear in the given order.
+fn components(mut text: &str, designators: &[(char, i128)]) -> Option<i128> {
+  let mut total = 0i128;
+  let mut designators = designators.iter();
+  while !text.is_empty() {
+    let end = text.find(|c: char| !(c.is_ascii_digit() || c == '.' || c == ','))?;
+    let designator = text[end..].chars().next()?.to_ascii_uppercase();
+    let &(_, seconds) = designators.find(|(d, _)| *d == designator)?;
+    total = total.checked_add(scale(
+      &text[..end].replace(',', "."),
+      seconds * NANOS_PER_SEC,
+    )?)?;
+    text = &text[end + 1..];
+  }
+  Some(total)
+}
+
+fn parse_units(mut text: &str) -> Option<i128> {
+  let mut total = 0i128;
+  while !text.is_empty() {
+    let end = text.find(|c: char| !(c.is_ascii_digit() || c == '.'))?;
+    let rest = &text[end..];
+    let (unit, nanos) = UNITS
+      .iter()
+      .filter(|(unit, _)| rest.starts_with(unit))
+      .max_by_key(|(unit, _)| unit.len())?;
+    total = total.checked_add(scale(&text[..end], *nanos)?)?;
+    text = &rest[unit.len()..];
+  }
+  Some(total)
+}
+
+/// Multiplies a decimal string by `unit` without going through floating point.
+fn scale(number: &str, unit: i128) -> Option<i128> {
+  let (integer, fraction) = number.split_once('.').unwrap_or((number, ""));
+  if integer.is_empty() && fraction.is_empty() {
+    return None;
+  }
+  let integer = if integer.is_empty() {
+    0
+  } else {
+    integer.parse::<i128>().ok()?
+  };
+  let fraction_value = if fraction.is_empty() {
+    0
+  } else {
+    fraction.parse::<i128>().ok()?
+  };
+  let denominator = 10i128.checked_pow(fraction.len() as u32)?;
+  integer
+    .checked_mul(unit)?
+    .checked_add(fraction_value.checked_mul(unit)? / denominator)
+}
+
+fn decimal(nanos: i128, unit: i128) -> String {
+  let fraction = format!("{:09}", (nanos % unit) * NANOS_PER_SEC / unit);
+  let fraction = fraction.trim_end_matches('0');
+  if fraction.is_empty() {
+    format!("{}", nanos / unit)
+  } else {
+    format!("{}.{fraction}", nanos / unit)
+  }
+}
+
+fn iso8601(nanos: i128) -> String {
+  let hours = nanos / (3_600 * NANOS_PER_SEC);
+  let minutes = nanos / (60 * NANOS_PER_SEC) % 60;
+  let seconds = nanos % (60 * NANOS_PER_SEC);
+  let mut iso = String::from("PT");
+  if hours > 0 {
+    let _ = write!(iso, "{hours}H");
+  }
+  if minutes > 0 {
+    let _ = write!(iso, "{minutes}M");
+  }
+  if seconds > 0 || nanos == 0 {
+    let _ = write!(iso, "{}S", decimal(seconds, NANOS_PER_SEC));
+  }
+  iso
+}
+
+/// Matches Go's `time.Duration.String`.
+fn go(nanos: i128) -> String {
+  match nanos {
+    0 => String::from("0s"),
+    1..1_000 => format!("{nanos}ns"),
+    1_000..1_000_000 => format!("{}µs", decimal(nanos, 1_000)),
+    1_000_000..NANOS_PER_SEC => format!("{}ms", decimal(nanos, 1_000_000)),
+    _ => {
+      let hours = nanos / (3_600 * NANOS_PER_SEC);
+      let minutes = nanos / (60 * NANOS_PER_SEC) % 60;
+      let seconds = decimal(nanos % (60 * NANOS_PER_SEC), NANOS_PER_SEC);
+      match (hours, minutes) {
+        (0, 0) => format!("{seconds}s"),
+        (0, _) => format!("{minutes}m{seconds}s"),
+        _ => format!("{hours}h{minutes}m{seconds}s"),
+      }
+    }
+  }
+}
+
+fn compact(mut nanos: i128) -> String {
+  if nanos == 0 {
+    return String::from("0s");
+  }
+  let mut compact = String::new();
+  for (unit, unit_nanos) in UNITS
+    .iter()
+    .rev()
+    .filter(|(unit, _)| !matches!(*unit, "w" | "µs" | "μs"))
+  {
+    if nanos >= *unit_nanos {
+      let _ = write!(compact, "{}{unit}", nanos / unit_nanos);
+      nanos %= unit_nanos;
+    }
+  }
+  compact
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use ropey::Rope;
+  use strum::IntoEnumIterator;
+
+  fn convert(text: &str) -> Vec<String> {
+    DurationFormat::iter()
+      .filter_map(|format| format.transform(Rope::from_str(text).slice(..)))
+      .collect()
+  }
+
+  #[test]
+  fn test_duration_round_trip() {
+    ["86400", "PT24H", "24h0m0s", "1d"]
+      .into_iter()
+      .for_each(|text| assert_eq!(convert(text), ["86400", "PT24H", "24h0m0s", "1d"]));
+    assert_eq!(
+      convert("P1DT1H30M"),
+      ["91800", "PT25H30M", "25h30m0s", "1d1h30m"]
+    );
+  }
+
+  #[test]
+  fn test_duration_fractional() {
+    assert_eq!(convert("1.5"), ["1.5", "PT1.5S", "1.5s", "1s500ms"]);
+    assert_eq!(convert("PT0,25S"), ["0.25", "PT0.25S", "250ms", "250ms"]);
+    assert_eq!(convert("1.5h"), ["5400", "PT1H30M", "1h30m0s", "1h30m"]);
+    assert_eq!(
+      convert("1m0.000002s"),
+      ["60.000002", "PT1M0.000002S", "1m0.000002s", "1m2us"]
+    );
+    assert_eq!(
+      convert("-1500µs"),
+      ["-0.0015", "-PT0.0015S", "-1.5ms", "-1ms500us"]
+    );
+  }
+
+  #[test]
+  fn test_duration_rejects() {
+    ["P1Y", "P", "PT", "1x", "abc", "", "1h30"]
+      .into_iter()
+      .for_each(|text| assert_eq!(parse(text), None, "{text}"));
+    let days = "1000000000000000000000000";
+    [
+      format!("{days}d{days}d"),
+      format!("P{days}DT24000000000000000000000000H"),
+      format!("P142857142857142857142857W{days}D"),
+    ]
+    .iter()
+    .for_each(|text| assert_eq!(parse(text), None, "{text}"));
+  }
+}
@@ -6,6 +6,18 @@ use chrono::{DateTime, SecondsFormat, Utc};
 use ropey::RopeSlice;
 use tower_lsp::lsp_types::{CodeActionKind, Range};
 
+impl EpochToUTC {
+  pub fn format(secs: i64) -> Option<String> {
+    Self::format_with_nanos(secs, 0)
+  }
+
+  /// Sub-second digits are only shown when they are non-zero.
+  pub fn format_with_nanos(secs: i64, nanos: u32) -> Option<String> {
+    DateTime::<Utc>::from_timestamp(secs, nanos)
+      .map(|datetime| datetime.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+  }
+}
+
 impl CommandMeta for EpochToUTC {
   fn command_name(&self) -> &'static str {
     "text-language-server.epoch-to-utc"
@@ -34,7 +46,6 @@ impl Transform for EpochToUTC {
       .to_string()
       .parse::<i64>()
       .ok()
-      .and_then(|secs| DateTime::<Utc>::from_timestamp(secs, 0))
-      .map(|datetime| datetime.to_rfc3339_opts(SecondsFormat::Secs, true))
+      .and_then(EpochToUTC::format)
   }
 }
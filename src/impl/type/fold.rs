@@ -0,0 +1,183 @@
+use crate::{
+  r#trait::{CommandMeta, Text, Transform},
+  r#type::{Config, Fold, Slugify},
+};
+use ropey::RopeSlice;
+use tower_lsp::lsp_types::{CodeActionKind, Range};
+use unicode_normalization::{UnicodeNormalization, char::is_combining_mark};
+
+/// Latin, Greek and Cyrillic, where marks are accents that can be dropped. Elsewhere they can be
+/// vowels or consonant modifiers, as in Devanagari, Arabic or Hebrew.
+fn accented_script(c: char) -> bool {
+  matches!(
+    c,
+    '\u{0000}'..='\u{024f}'
+      | '\u{0370}'..='\u{052f}'
+      | '\u{1c80}'..='\u{1c8f}'
+      | '\u{1e00}'..='\u{1fff}'
+      | '\u{2c60}'..='\u{2c7f}'
+      | '\u{2de0}'..='\u{2dff}'
+      | '\u{a640}'..='\u{a69f}'
+      | '\u{a720}'..='\u{a7ff}'
+      | '\u{ab30}'..='\u{ab6f}'
+  )
+}
+
+impl Fold {
+  /// Stripping diacritics only removes combining marks from Latin, Greek and Cyrillic letters,
+  /// so other scripts and letters such as `ø` or `ß` survive. Transliteration maps everything to
+  /// ASCII.
+  pub fn fold(&self, text: &str) -> String {
+    match self {
+      Fold::StripDiacritics => {
+        let mut accented = false;
+        text
+          .nfd()
+          .filter(|&c| {
+            if !is_combining_mark(c) {
+              accented = accented_script(c);
+              return true;
+            }
+            !accented
+          })
+          .nfc()
+          .collect()
+      }
+      Fold::Transliterate => any_ascii::any_ascii(text),
+    }
+  }
+}
+
+impl CommandMeta for Fold {
+  fn command_name(&self) -> &'static str {
+    match self {
+      Fold::StripDiacritics => "text-language-server.strip-diacritics",
+      Fold::Transliterate => "text-language-server.transliterate",
+    }
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    match self {
+      Fold::StripDiacritics => "Strip diacritics",
+      Fold::Transliterate => "Transliterate to ASCII",
+    }
+  }
+}
+
+impl Transform for Fold {
+  fn code_action_kind(&self) -> Vec<CodeActionKind> {
+    vec![CodeActionKind::REFACTOR_REWRITE, CodeActionKind::SOURCE]
+  }
+
+  fn code_action_condition(&self, source: RopeSlice, range: Range) -> bool {
+    let text = source.slice(source.range(range)).to_string();
+    self.fold(&text) != text
+  }
+
+  fn transform(&self, text: RopeSlice) -> Option<String> {
+    Some(self.fold(&text.to_string()))
+  }
+}
+
+impl Slugify {
+  fn slugify(&self, text: &str) -> String {
+    let words = Fold::Transliterate
+      .fold(text)
+      .to_lowercase()
+      .split(|c: char| !c.is_ascii_alphanumeric())
+      .filter(|word| !word.is_empty())
+      .map(String::from)
+      .collect::<Vec<_>>();
+    let Some(max_length) = self.max_length else {
+      return words.join(&self.separator);
+    };
+    // Whole words where they fit; a first word longer than the limit is cut.
+    let mut slug = String::new();
+    for word in words {
+      let separator = if slug.is_empty() { "" } else { &self.separator };
+      if slug.len() + separator.len() + word.len() > max_length {
+        if slug.is_empty() {
+          slug = word[..max_length].to_string();
+        }
+        break;
+      }
+      slug.push_str(separator);
+      slug.push_str(&word);
+    }
+    slug
+  }
+}
+
+impl From<&Config> for Slugify {
+  fn from(config: &Config) -> Self {
+    Self {
+      separator: config.slug_separator.clone(),
+      max_length: config.slug_max_length,
+    }
+  }
+}
+
+impl CommandMeta for Slugify {
+  fn command_name(&self) -> &'static str {
+    "text-language-server.slugify"
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    "Slugify"
+  }
+}
+
+impl Transform for Slugify {
+  fn code_action_kind(&self) -> Vec<CodeActionKind> {
+    vec![CodeActionKind::REFACTOR_REWRITE, CodeActionKind::SOURCE]
+  }
+
+  fn code_action_condition(&self, source: RopeSlice, range: Range) -> bool {
+    let text = source.slice(source.range(range));
+    self
+      .transform(text)
+      .is_some_and(|slug| slug != text.to_string().trim())
+  }
+
+  fn transform(&self, text: RopeSlice) -> Option<String> {
+    Some(self.slugify(&text.to_string())).filter(|slug| !slug.is_empty())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_fold() {
+    assert_eq!(
+      Fold::StripDiacritics.fold("Crème brûlée, Øresund, Ελληνικά"),
+      "Creme brulee, Øresund, Ελληνικα"
+    );
+    assert_eq!(Fold::StripDiacritics.fold("हिन्दी, עִבְרִית"), "हिन्दी, עִבְרִית");
+    assert_eq!(
+      Fold::Transliterate.fold("Crème brûlée, Øresund, Ελληνικά, Москва"),
+      "Creme brulee, Oresund, Ellinika, Moskva"
+    );
+  }
+
+  #[test]
+  fn test_slugify() {
+    let slugify = |separator: &str, max_length| Slugify {
+      separator: separator.to_string(),
+      max_length,
+    };
+    assert_eq!(
+      slugify("-", None).slugify("  Ärger über Straße & Co. — 2024!  "),
+      "arger-uber-strasse-co-2024"
+    );
+    assert_eq!(
+      slugify("_", Some(16)).slugify("Ärger über Straße & Co."),
+      "arger_uber"
+    );
+    assert_eq!(
+      slugify("-", Some(4)).slugify("Internationalisation"),
+      "inte"
+    );
+  }
+}
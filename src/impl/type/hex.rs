@@ -0,0 +1,254 @@
+use crate::{
+  r#trait::{CommandMeta, Text, Transform},
+  r#type::{HexToText, Hexdump, ParseHexdump, TextToHex},
+};
+use ropey::RopeSlice;
+use std::fmt::Write;
+use tower_lsp::lsp_types::{CodeActionKind, Range};
+
+const BYTES_PER_LINE: usize = 16;
+
+/// `0x` only counts as a prefix of a token, and `\x` as a prefix of every byte in one.
+fn hex_bytes(hex: &str) -> Option<Vec<u8>> {
+  let digits = hex
+    .split(|c: char| c.is_whitespace() || c == ',' || c == ':')
+    .flat_map(|token| match token.strip_prefix("\\x") {
+      Some(_) => token.split("\\x").skip(1).collect::<Vec<_>>(),
+      None => vec![
+        token
+          .strip_prefix("0x")
+          .or_else(|| token.strip_prefix("0X"))
+          .unwrap_or(token),
+      ],
+    })
+    .flat_map(str::chars)
+    .collect::<Vec<_>>();
+  if digits.is_empty() || digits.len() % 2 != 0 {
+    return None;
+  }
+  digits
+    .chunks(2)
+    .map(|pair| {
+      let high = pair[0].to_digit(16)?;
+      let low = pair[1].to_digit(16)?;
+      Some((high * 16 + low) as u8)
+    })
+    .collect()
+}
+
+/// One line of `xxd` output: offset, two-byte groups and an ASCII gutter.
+fn dump_line(offset: usize, bytes: &[u8]) -> String {
+  let hex = bytes
+    .chunks(2)
+    .map(|group| {
+      group
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>()
+    })
+    .collect::<Vec<_>>()
+    .join(" ");
+  let ascii = bytes
+    .iter()
+    .map(|byte| {
+      if byte.is_ascii_graphic() || *byte == b' ' {
+        *byte as char
+      } else {
+        '.'
+      }
+    })
+    .collect::<String>();
+  format!("{offset:08x}: {hex:<39}  {ascii}")
+}
+
+/// Bytes of one dump line. The gutter is ignored, so edits only need to be made on the hex side.
+fn undump_line(line: &str) -> Option<Vec<u8>> {
+  let (offset, rest) = line.split_once(": ")?;
+  if offset.is_empty() || !offset.chars().all(|c| c.is_ascii_hexdigit()) {
+    return None;
+  }
+  let hex = rest.split("  ").next()?;
+  if hex.trim().is_empty() {
+    return Some(Vec::new());
+  }
+  hex_bytes(hex).filter(|bytes| bytes.len() <= BYTES_PER_LINE)
+}
+
+fn undump(text: &str) -> Option<Vec<u8>> {
+  text
+    .lines()
+    .filter(|line| !line.trim().is_empty())
+    .map(undump_line)
+    .collect::<Option<Vec<_>>>()
+    .filter(|lines| !lines.is_empty())
+    .map(|lines| lines.concat())
+}
+
+impl CommandMeta for HexToText {
+  fn command_name(&self) -> &'static str {
+    "text-language-server.hex-to-text"
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    "Hex to text"
+  }
+}
+
+impl Transform for HexToText {
+  fn code_action_kind(&self) -> Vec<CodeActionKind> {
+    vec![CodeActionKind::REFACTOR_REWRITE, CodeActionKind::SOURCE]
+  }
+
+  fn code_action_condition(&self, source: RopeSlice, range: Range) -> bool {
+    self.transform(source.slice(source.range(range))).is_some()
+  }
+
+  /// Only printable text with a letter or digit in it counts, so numbers such as `1234` that
+  /// happen to be valid hex aren't turned into control characters.
+  fn transform(&self, text: RopeSlice) -> Option<String> {
+    String::from_utf8(hex_bytes(&text.to_string())?)
+      .ok()
+      .filter(|text| {
+        text.chars().any(char::is_alphanumeric)
+          && text
+            .chars()
+            .all(|c| !c.is_control() || matches!(c, '\n' | '\r' | '\t'))
+      })
+  }
+}
+
+impl CommandMeta for TextToHex {
+  fn command_name(&self) -> &'static str {
+    "text-language-server.text-to-hex"
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    "Text to hex"
+  }
+}
+
+impl Transform for TextToHex {
+  fn code_action_kind(&self) -> Vec<CodeActionKind> {
+    vec![CodeActionKind::REFACTOR_REWRITE, CodeActionKind::SOURCE]
+  }
+
+  fn code_action_condition(&self, _: RopeSlice, range: Range) -> bool {
+    range.start != range.end
+  }
+
+  fn transform(&self, text: RopeSlice) -> Option<String> {
+    text
+      .bytes()
+      .fold(String::new(), |mut hex, byte| {
+        let _ = write!(hex, "{byte:02x}");
+        hex
+      })
+      .into()
+  }
+}
+
+impl CommandMeta for Hexdump {
+  fn command_name(&self) -> &'static str {
+    "text-language-server.hexdump"
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    "Hexdump"
+  }
+}
+
+impl Transform for Hexdump {
+  fn code_action_kind(&self) -> Vec<CodeActionKind> {
+    vec![CodeActionKind::REFACTOR_REWRITE, CodeActionKind::SOURCE]
+  }
+
+  fn code_action_condition(&self, source: RopeSlice, range: Range) -> bool {
+    range.start != range.end && undump(&source.slice(source.range(range)).to_string()).is_none()
+  }
+
+  fn transform(&self, text: RopeSlice) -> Option<String> {
+    text
+      .bytes()
+      .collect::<Vec<_>>()
+      .chunks(BYTES_PER_LINE)
+      .enumerate()
+      .map(|(idx, bytes)| dump_line(idx * BYTES_PER_LINE, bytes))
+      .collect::<Vec<_>>()
+      .join("\n")
+      .into()
+  }
+}
+
+impl CommandMeta for ParseHexdump {
+  fn command_name(&self) -> &'static str {
+    "text-language-server.parse-hexdump"
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    "Hexdump to text"
+  }
+}
+
+impl Transform for ParseHexdump {
+  fn code_action_kind(&self) -> Vec<CodeActionKind> {
+    vec![CodeActionKind::REFACTOR_REWRITE, CodeActionKind::SOURCE]
+  }
+
+  fn code_action_condition(&self, source: RopeSlice, range: Range) -> bool {
+    self.transform(source.slice(source.range(range))).is_some()
+  }
+
+  fn transform(&self, text: RopeSlice) -> Option<String> {
+    String::from_utf8(undump(&text.to_string())?).ok()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use ropey::Rope;
+
+  fn apply(transform: &impl Transform, text: &str) -> Option<String> {
+    transform.transform(Rope::from_str(text).slice(..))
+  }
+
+  #[test]
+  fn test_hex_to_text() {
+    assert_eq!(apply(&TextToHex, "Hi ✓").as_deref(), Some("486920e29c93"));
+    for hex in [
+      "486920e29c93",
+      "48 69 20 E2 9C 93",
+      "0x48 0x69 0x20 0xe2 0x9c 0x93",
+      "\\x48\\x69\\x20\\xe2\\x9c\\x93",
+    ] {
+      assert_eq!(apply(&HexToText, hex).as_deref(), Some("Hi ✓"));
+    }
+    assert_eq!(apply(&HexToText, "486"), None);
+    assert_eq!(apply(&HexToText, "ff"), None);
+    assert_eq!(apply(&HexToText, "1234"), None);
+    assert_eq!(apply(&HexToText, "2025"), None);
+    assert_eq!(apply(&HexToText, "40x41"), None);
+    assert_eq!(apply(&HexToText, "0x48,0x69").as_deref(), Some("Hi"));
+  }
+
+  #[test]
+  fn test_hexdump_round_trip() {
+    let text = "Hello, world!\nSecond line, longer than sixteen bytes.";
+    let dump = apply(&Hexdump, text).unwrap();
+    assert_eq!(
+      dump.lines().next(),
+      Some("00000000: 4865 6c6c 6f2c 2077 6f72 6c64 210a 5365  Hello, world!.Se")
+    );
+    assert_eq!(
+      dump.lines().last(),
+      Some("00000030: 7974 6573 2e                             ytes.")
+    );
+    assert_eq!(apply(&ParseHexdump, &dump).as_deref(), Some(text));
+    let edited = dump.replacen("4865 6c6c", "4a65 6c6c", 1);
+    assert_eq!(
+      apply(&ParseHexdump, &edited).as_deref(),
+      Some(text.replacen('H', "J", 1).as_str())
+    );
+    assert_eq!(apply(&ParseHexdump, "not a dump"), None);
+  }
+}
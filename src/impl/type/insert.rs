@@ -0,0 +1,74 @@
+use crate::{
+  r#trait::{CommandMeta, Generate, Transform},
+  r#type::Insert,
+};
+use ropey::RopeSlice;
+use tower_lsp::lsp_types::{CodeActionKind, Range, TextEdit};
+
+impl<T: CommandMeta> CommandMeta for Insert<T> {
+  fn command_name(&self) -> &'static str {
+    self.0.command_name()
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    self.0.command_display_name()
+  }
+}
+
+impl<T: Generate> Transform for Insert<T> {
+  fn code_action_kind(&self) -> Vec<CodeActionKind> {
+    vec![CodeActionKind::SOURCE]
+  }
+
+  fn code_action_condition(&self, _: RopeSlice, range: Range) -> bool {
+    range.start == range.end
+  }
+
+  fn transform(&self, _: RopeSlice) -> Option<String> {
+    self.0.generate()
+  }
+
+  /// A missing range falls back to the whole document, which must not be overwritten.
+  fn validate(&self, _: RopeSlice, range: Range) -> Result<(), String> {
+    if range.start == range.end {
+      Ok(())
+    } else {
+      Err(String::from(
+        "Inserting needs a cursor position, not a selection",
+      ))
+    }
+  }
+
+  fn text_edits(&self, _: RopeSlice, range: Range) -> Option<Vec<TextEdit>> {
+    self.0.generate().map(|new_text| {
+      vec![TextEdit {
+        range: Range::new(range.start, range.start),
+        new_text,
+      }]
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{r#trait::Text, r#type::LoremIpsum};
+  use ropey::Rope;
+  use tower_lsp::lsp_types::Position;
+
+  #[test]
+  fn test_insert_keeps_document() {
+    let rope = Rope::from_str("first\nsecond\n");
+    let source = rope.slice(..);
+    let range = source.range_full();
+    assert!(Insert(LoremIpsum).validate(source, range).is_err());
+    let edits = Insert(LoremIpsum).text_edits(source, range).unwrap();
+    assert_eq!(edits.len(), 1);
+    assert_eq!(
+      edits[0].range,
+      Range::new(Position::new(0, 0), Position::new(0, 0))
+    );
+    let cursor = Range::new(Position::new(1, 3), Position::new(1, 3));
+    assert_eq!(Insert(LoremIpsum).validate(source, cursor), Ok(()));
+  }
+}
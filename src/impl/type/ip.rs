@@ -0,0 +1,267 @@
+use crate::{
+  r#trait::{CommandMeta, Text, Transform},
+  r#type::{CidrInfo, IpFormat, SummarizeNetworks},
+};
+use ipnet::IpNet;
+use ropey::RopeSlice;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use tower_lsp::lsp_types::{CodeActionKind, Range};
+
+const IPV6_ADDRESSES: &str = "340282366920938463463374607431768211456";
+
+impl CommandMeta for CidrInfo {
+  fn command_name(&self) -> &'static str {
+    "text-language-server.cidr-info"
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    "Expand CIDR"
+  }
+}
+
+impl Transform for CidrInfo {
+  fn code_action_kind(&self) -> Vec<CodeActionKind> {
+    vec![CodeActionKind::QUICKFIX, CodeActionKind::SOURCE]
+  }
+
+  fn code_action_condition(&self, source: RopeSlice, range: Range) -> bool {
+    self.transform(source.slice(source.range(range))).is_some()
+  }
+
+  /// IPv4 networks leave out the network and broadcast addresses from the host range, except
+  /// for `/31` point-to-point links and `/32` single hosts. IPv6 has no broadcast address.
+  fn transform(&self, text: RopeSlice) -> Option<String> {
+    let net = text.to_string().trim().parse::<IpNet>().ok()?.trunc();
+    let host_bits = u32::from(net.max_prefix_len() - net.prefix_len());
+    // 2^128 addresses in `::/0` is one more than `u128` holds.
+    let addresses = 1u128
+      .checked_shl(host_bits)
+      .map_or_else(|| String::from(IPV6_ADDRESSES), |count| count.to_string());
+    match net {
+      IpNet::V4(v4) => {
+        let (first, last, hosts) = match host_bits {
+          0 | 1 => (v4.network(), v4.broadcast(), addresses),
+          _ => (
+            Ipv4Addr::from(u32::from(v4.network()) + 1),
+            Ipv4Addr::from(u32::from(v4.broadcast()) - 1),
+            ((1u64 << host_bits) - 2).to_string(),
+          ),
+        };
+        format!(
+          "network: {net}\nnetmask: {}\nbroadcast: {}\nfirst host: {first}\nlast host: {last}\nhosts: {hosts}",
+          v4.netmask(),
+          v4.broadcast(),
+        )
+      }
+      IpNet::V6(v6) => format!(
+        "network: {net}\nfirst address: {}\nlast address: {}\naddresses: {addresses}",
+        v6.network(),
+        v6.broadcast(),
+      ),
+    }
+    .into()
+  }
+}
+
+impl IpFormat {
+  fn format(&self, text: &str) -> Option<String> {
+    match self {
+      IpFormat::Integer => Some(u32::from(text.parse::<Ipv4Addr>().ok()?).to_string()),
+      // Smaller numbers would be addresses in `0.0.0.0/8`, which no one writes as an integer.
+      IpFormat::DottedQuad => Some(
+        Ipv4Addr::from(text.parse::<u32>().ok().filter(|&value| value >= 1 << 24)?).to_string(),
+      ),
+      IpFormat::Compressed | IpFormat::Expanded => {
+        // A prefix length is carried over unchanged.
+        let (address, prefix) = text.split_at(text.find('/').unwrap_or(text.len()));
+        let address = address.parse::<Ipv6Addr>().ok()?;
+        let address = match self {
+          IpFormat::Compressed => address.to_string(),
+          _ => address
+            .segments()
+            .map(|segment| format!("{segment:04x}"))
+            .join(":"),
+        };
+        Some(format!("{address}{prefix}"))
+      }
+    }
+  }
+}
+
+impl CommandMeta for IpFormat {
+  fn command_name(&self) -> &'static str {
+    match self {
+      IpFormat::Integer => "text-language-server.ip-to-integer",
+      IpFormat::DottedQuad => "text-language-server.integer-to-ip",
+      IpFormat::Compressed => "text-language-server.ipv6-compress",
+      IpFormat::Expanded => "text-language-server.ipv6-expand",
+    }
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    match self {
+      IpFormat::Integer => "IPv4 to integer",
+      IpFormat::DottedQuad => "Integer to IPv4",
+      IpFormat::Compressed => "Compress IPv6",
+      IpFormat::Expanded => "Expand IPv6",
+    }
+  }
+}
+
+impl Transform for IpFormat {
+  fn code_action_kind(&self) -> Vec<CodeActionKind> {
+    vec![CodeActionKind::QUICKFIX, CodeActionKind::SOURCE]
+  }
+
+  fn code_action_condition(&self, source: RopeSlice, range: Range) -> bool {
+    let text = source.slice(source.range(range));
+    self
+      .transform(text)
+      .is_some_and(|result| result != text.to_string().trim())
+  }
+
+  fn transform(&self, text: RopeSlice) -> Option<String> {
+    self.format(text.to_string().trim())
+  }
+}
+
+/// Bare addresses count as single-host networks.
+fn network(line: &str) -> Option<IpNet> {
+  line
+    .parse::<IpNet>()
+    .ok()
+    .or_else(|| line.parse::<IpAddr>().ok().map(IpNet::from))
+}
+
+impl CommandMeta for SummarizeNetworks {
+  fn command_name(&self) -> &'static str {
+    "text-language-server.summarize-networks"
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    "Summarize addresses and CIDRs"
+  }
+}
+
+impl Transform for SummarizeNetworks {
+  fn code_action_kind(&self) -> Vec<CodeActionKind> {
+    vec![CodeActionKind::QUICKFIX, CodeActionKind::SOURCE]
+  }
+
+  fn code_action_condition(&self, source: RopeSlice, range: Range) -> bool {
+    let text = source.slice(source.range(range));
+    text.to_string().trim().contains('\n')
+      && self.transform(text).is_some_and(|summary| summary != text)
+  }
+
+  /// IPv4 networks come before IPv6, each in ascending order.
+  fn transform(&self, text: RopeSlice) -> Option<String> {
+    let text = text.to_string();
+    let networks = text
+      .lines()
+      .map(str::trim)
+      .filter(|line| !line.is_empty())
+      .map(network)
+      .collect::<Option<Vec<_>>>()?;
+    let mut summary = IpNet::aggregate(&networks)
+      .iter()
+      .map(ToString::to_string)
+      .collect::<Vec<_>>()
+      .join("\n");
+    if text.ends_with('\n') {
+      summary.push('\n');
+    }
+    Some(summary)
+  }
+
+  fn validate(&self, source: RopeSlice, range: Range) -> Result<(), String> {
+    source
+      .slice(source.range(range))
+      .to_string()
+      .lines()
+      .enumerate()
+      .find(|(_, line)| !line.trim().is_empty() && network(line.trim()).is_none())
+      .map_or(Ok(()), |(idx, line)| {
+        Err(format!(
+          "Invalid address or CIDR on line {}: {:?}",
+          range.start.line as usize + idx + 1,
+          line.trim()
+        ))
+      })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use ropey::Rope;
+  use tower_lsp::lsp_types::Position;
+
+  #[test]
+  fn test_cidr_info_and_format() {
+    assert_eq!(
+      CidrInfo
+        .transform(Rope::from_str("10.1.2.3/22").slice(..))
+        .as_deref(),
+      Some(
+        "network: 10.1.0.0/22\nnetmask: 255.255.252.0\nbroadcast: 10.1.3.255\nfirst host: 10.1.0.1\nlast host: 10.1.3.254\nhosts: 1022"
+      )
+    );
+    assert!(
+      CidrInfo
+        .transform(Rope::from_str("192.0.2.7/31").slice(..))
+        .unwrap()
+        .ends_with("first host: 192.0.2.6\nlast host: 192.0.2.7\nhosts: 2")
+    );
+    assert_eq!(
+      CidrInfo
+        .transform(Rope::from_str("2001:db8::/32").slice(..))
+        .as_deref(),
+      Some(
+        "network: 2001:db8::/32\nfirst address: 2001:db8::\nlast address: 2001:db8:ffff:ffff:ffff:ffff:ffff:ffff\naddresses: 79228162514264337593543950336"
+      )
+    );
+    assert_eq!(
+      IpFormat::Integer.format("192.168.1.10").as_deref(),
+      Some("3232235786")
+    );
+    assert_eq!(
+      IpFormat::DottedQuad.format("3232235786").as_deref(),
+      Some("192.168.1.10")
+    );
+    assert_eq!(
+      IpFormat::Expanded.format("2001:db8::1/64").as_deref(),
+      Some("2001:0db8:0000:0000:0000:0000:0000:0001/64")
+    );
+    assert_eq!(
+      IpFormat::Compressed
+        .format("2001:0db8:0000:0000:0000:0000:0000:0001/64")
+        .as_deref(),
+      Some("2001:db8::1/64")
+    );
+    assert_eq!(IpFormat::DottedQuad.format("4294967296"), None);
+    assert_eq!(IpFormat::DottedQuad.format("42"), None);
+    assert_eq!(
+      IpFormat::DottedQuad.format("16777216").as_deref(),
+      Some("1.0.0.0")
+    );
+  }
+
+  #[test]
+  fn test_summarize_networks() {
+    let rope = Rope::from_str(
+      "10.0.1.0/24\n10.0.0.0/24\n2001:db8::1\n10.0.2.5\n\n10.0.0.128/25\n2001:db8::/127\n",
+    );
+    let source = rope.slice(..);
+    assert_eq!(
+      SummarizeNetworks.transform(source).as_deref(),
+      Some("10.0.0.0/23\n10.0.2.5/32\n2001:db8::/127\n")
+    );
+    let rope = Rope::from_str("header\n10.0.0.0/24\n10.0.0.300\n");
+    let source = rope.slice(..);
+    assert_eq!(
+      SummarizeNetworks.validate(source, Range::new(Position::new(1, 0), Position::new(3, 0))),
+      Err("Invalid address or CIDR on line 3: \"10.0.0.300\"".to_string())
+    );
+  }
+}
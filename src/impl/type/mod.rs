@@ -1,3 +1,30 @@
+mod append;
+mod byte_size;
+mod calculate;
+mod color;
+mod compression;
+mod config;
+mod cron;
+mod decode_jwt;
+mod digest;
+mod domain;
+mod duration;
 mod epoch_to_utc;
+mod fold;
+mod hex;
+mod insert;
+mod ip;
+mod random;
+mod reflow;
+mod regex_replace;
+mod shell;
 mod source;
+mod sql;
+mod string_literal;
+mod timestamp;
+mod typography;
 mod unescape;
+mod uuid;
+mod version;
+mod whitespace;
+mod xml;
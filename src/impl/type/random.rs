@@ -0,0 +1,216 @@
+use crate::{
+  r#trait::{CommandMeta, Generate},
+  r#type::{Config, LoremIpsum, Password, RandomInt, RandomToken, TokenEncoding},
+};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use rand::{Rng, RngCore, seq::SliceRandom};
+
+const LOWERCASE: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+const UPPERCASE: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const DIGITS: &[u8] = b"0123456789";
+const SYMBOLS: &[u8] = b"!#$%&()*+,-./:;<=>?@[]^_{|}~";
+/// Client settings beyond these are clamped rather than allocated.
+const MAX_PASSWORD_LENGTH: usize = 4096;
+const MAX_TOKEN_BYTES: usize = 4096;
+const LOREM_IPSUM: &str = "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor incididunt ut labore et dolore magna aliqua. Ut enim ad minim veniam, quis nostrud exercitation ullamco laboris nisi ut aliquip ex ea commodo consequat. Duis aute irure dolor in reprehenderit in voluptate velit esse cillum dolore eu fugiat nulla pariatur. Excepteur sint occaecat cupidatat non proident, sunt in culpa qui officia deserunt mollit anim id est laborum.";
+
+impl Default for Password {
+  fn default() -> Self {
+    Self {
+      length: 20,
+      lowercase: true,
+      uppercase: true,
+      digits: true,
+      symbols: true,
+    }
+  }
+}
+
+impl CommandMeta for Password {
+  fn command_name(&self) -> &'static str {
+    "text-language-server.random-password"
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    "Random password"
+  }
+}
+
+impl Generate for Password {
+  fn generate(&self) -> Option<String> {
+    let classes = [
+      (self.lowercase, LOWERCASE),
+      (self.uppercase, UPPERCASE),
+      (self.digits, DIGITS),
+      (self.symbols, SYMBOLS),
+    ]
+    .into_iter()
+    .filter_map(|(enabled, class)| enabled.then_some(class))
+    .collect::<Vec<_>>();
+    let length = self.length.min(MAX_PASSWORD_LENGTH);
+    if classes.is_empty() || length < classes.len() {
+      return None;
+    }
+    let alphabet = classes.concat();
+    // `rand::rng()` is a CSPRNG seeded from the operating system.
+    let mut rng = rand::rng();
+    // One character from every enabled class, the rest from all of them, then shuffled.
+    let mut password = classes
+      .iter()
+      .map(|class| class[rng.random_range(..class.len())])
+      .collect::<Vec<_>>();
+    while password.len() < length {
+      password.push(alphabet[rng.random_range(..alphabet.len())]);
+    }
+    password.shuffle(&mut rng);
+    String::from_utf8(password).ok()
+  }
+}
+
+impl RandomToken {
+  pub fn new(encoding: TokenEncoding, config: &Config) -> Self {
+    Self {
+      encoding,
+      bytes: config.random_token_bytes.min(MAX_TOKEN_BYTES),
+    }
+  }
+}
+
+impl CommandMeta for RandomToken {
+  fn command_name(&self) -> &'static str {
+    match self.encoding {
+      TokenEncoding::Hex => "text-language-server.random-token-hex",
+      TokenEncoding::Base64Url => "text-language-server.random-token-base64url",
+    }
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    match self.encoding {
+      TokenEncoding::Hex => "Random hex token",
+      TokenEncoding::Base64Url => "Random Base64url token",
+    }
+  }
+}
+
+impl Generate for RandomToken {
+  fn generate(&self) -> Option<String> {
+    let mut bytes = vec![0; self.bytes];
+    rand::rng().fill_bytes(&mut bytes);
+    match self.encoding {
+      TokenEncoding::Hex => bytes.iter().map(|byte| format!("{byte:02x}")).collect(),
+      TokenEncoding::Base64Url => URL_SAFE_NO_PAD.encode(bytes),
+    }
+    .into()
+  }
+}
+
+impl Default for RandomInt {
+  fn default() -> Self {
+    Self { min: 0, max: 100 }
+  }
+}
+
+impl CommandMeta for RandomInt {
+  fn command_name(&self) -> &'static str {
+    "text-language-server.random-int"
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    "Random integer"
+  }
+}
+
+impl Generate for RandomInt {
+  fn generate(&self) -> Option<String> {
+    (self.min <= self.max).then(|| rand::rng().random_range(self.min..=self.max).to_string())
+  }
+}
+
+impl CommandMeta for LoremIpsum {
+  fn command_name(&self) -> &'static str {
+    "text-language-server.lorem-ipsum"
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    "Lorem ipsum paragraph"
+  }
+}
+
+impl Generate for LoremIpsum {
+  fn generate(&self) -> Option<String> {
+    Some(LOREM_IPSUM.to_string())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use strum::IntoEnumIterator;
+
+  #[test]
+  fn test_password_classes() {
+    let password = Password::default().generate().unwrap();
+    assert_eq!(password.len(), 20);
+    for class in [LOWERCASE, UPPERCASE, DIGITS, SYMBOLS] {
+      assert!(password.bytes().any(|byte| class.contains(&byte)));
+    }
+    let digits = Password {
+      length: 6,
+      lowercase: false,
+      uppercase: false,
+      symbols: false,
+      ..Default::default()
+    };
+    assert!(
+      digits
+        .generate()
+        .unwrap()
+        .bytes()
+        .all(|byte| byte.is_ascii_digit())
+    );
+    assert_eq!(
+      Password {
+        length: 3,
+        ..Default::default()
+      }
+      .generate(),
+      None
+    );
+    assert_eq!(
+      Password {
+        length: usize::MAX,
+        ..Default::default()
+      }
+      .generate()
+      .map(|password| password.len()),
+      Some(MAX_PASSWORD_LENGTH)
+    );
+  }
+
+  #[test]
+  fn test_random_token_and_int() {
+    let config = Config::default();
+    let lengths = TokenEncoding::iter()
+      .map(|encoding| {
+        RandomToken::new(encoding, &config)
+          .generate()
+          .unwrap()
+          .len()
+      })
+      .collect::<Vec<_>>();
+    assert_eq!(lengths, vec![64, 43]);
+    let config = Config {
+      random_token_bytes: usize::MAX,
+      ..Default::default()
+    };
+    assert_eq!(
+      RandomToken::new(TokenEncoding::Hex, &config)
+        .generate()
+        .map(|token| token.len()),
+      Some(2 * MAX_TOKEN_BYTES)
+    );
+    let int = RandomInt { min: -3, max: -1 }.generate().unwrap();
+    assert!((-3..=-1).contains(&int.parse::<i64>().unwrap()));
+    assert_eq!(RandomInt { min: 1, max: 0 }.generate(), None);
+  }
+}
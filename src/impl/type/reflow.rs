@@ -0,0 +1,296 @@
+use crate::{
+  r#trait::{CommandMeta, Text, Transform},
+  r#type::{Config, Reflow, Unwrap},
+};
+use ropey::RopeSlice;
+use std::iter;
+use tap::prelude::*;
+use tower_lsp::lsp_types::{CodeActionKind, Range};
+
+const COMMENT_MARKERS: [&str; 8] = ["///", "//!", "//", "#", ">", "--", ";", "*"];
+
+enum Block<'a> {
+  Blank(&'a str, &'static str),
+  Paragraph(Paragraph),
+}
+
+struct Paragraph {
+  marker: String,
+  first_prefix: String,
+  rest_prefix: String,
+  content: String,
+  /// Ending used between the lines a paragraph renders to, taken from its first line.
+  line_ending: &'static str,
+  /// Ending of the paragraph's last line, kept after its last rendered line.
+  ending: &'static str,
+}
+
+impl From<&Config> for Reflow {
+  fn from(config: &Config) -> Self {
+    Self {
+      width: config.reflow_width,
+    }
+  }
+}
+
+impl CommandMeta for Reflow {
+  fn command_name(&self) -> &'static str {
+    "text-language-server.reflow"
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    "Reflow"
+  }
+}
+
+impl Transform for Reflow {
+  fn code_action_kind(&self) -> Vec<CodeActionKind> {
+    vec![CodeActionKind::REFACTOR_REWRITE, CodeActionKind::SOURCE]
+  }
+
+  fn code_action_condition(&self, source: RopeSlice, range: Range) -> bool {
+    source
+      .slice(source.range(range))
+      .chars()
+      .any(|c| !c.is_whitespace())
+  }
+
+  fn transform(&self, text: RopeSlice) -> Option<String> {
+    render(&text.to_string(), |paragraph| wrap(paragraph, self.width)).pipe(Some)
+  }
+}
+
+impl CommandMeta for Unwrap {
+  fn command_name(&self) -> &'static str {
+    "text-language-server.unwrap"
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    "Unwrap"
+  }
+}
+
+impl Transform for Unwrap {
+  fn code_action_kind(&self) -> Vec<CodeActionKind> {
+    vec![CodeActionKind::REFACTOR_REWRITE, CodeActionKind::SOURCE]
+  }
+
+  fn code_action_condition(&self, source: RopeSlice, range: Range) -> bool {
+    source.slice(source.range(range)).chars().any(|c| c == '\n')
+  }
+
+  fn transform(&self, text: RopeSlice) -> Option<String> {
+    render(&text.to_string(), |paragraph| {
+      vec![format!(
+        "{}{}",
+        paragraph.first_prefix,
+        words(&paragraph.content).join(" ")
+      )]
+    })
+    .pipe(Some)
+  }
+}
+
+fn render(text: &str, f: impl Fn(&Paragraph) -> Vec<String>) -> String {
+  let mut output = String::new();
+  for block in blocks(text) {
+    match block {
+      Block::Blank(line, ending) => {
+        output.push_str(line);
+        output.push_str(ending);
+      }
+      Block::Paragraph(paragraph) => {
+        output.push_str(&f(&paragraph).join(paragraph.line_ending));
+        output.push_str(paragraph.ending);
+      }
+    }
+  }
+  output
+}
+
+fn blocks(text: &str) -> Vec<Block<'_>> {
+  let mut blocks = Vec::new();
+  for line in text.split_inclusive('\n') {
+    let (line, ending) = split_ending(line);
+    let (prefix, content) = line.split_at(prefix_len(line));
+    if content.trim().is_empty() {
+      blocks.push(Block::Blank(line, ending));
+      continue;
+    }
+    let marker = prefix.trim_end();
+    let bullet = bullet_len(content);
+    match blocks.last_mut() {
+      Some(Block::Paragraph(paragraph)) if bullet == 0 && paragraph.marker == marker => {
+        paragraph.content.push(' ');
+        paragraph.content.push_str(content.trim());
+        paragraph.ending = ending;
+      }
+      _ => blocks.push(Block::Paragraph(Paragraph {
+        marker: marker.to_string(),
+        first_prefix: line[..prefix.len() + bullet].to_string(),
+        rest_prefix: iter::once(prefix)
+          .chain(iter::repeat_n(" ", content[..bullet].chars().count()))
+          .collect(),
+        content: content[bullet..].trim().to_string(),
+        line_ending: if ending.is_empty() { "\n" } else { ending },
+        ending,
+      })),
+    }
+  }
+  blocks
+}
+
+fn split_ending(line: &str) -> (&str, &'static str) {
+  if let Some(line) = line.strip_suffix("\r\n") {
+    (line, "\r\n")
+  } else if let Some(line) = line.strip_suffix('\n') {
+    (line, "\n")
+  } else {
+    (line, "")
+  }
+}
+
+/// Length of the leading indentation and comment markers, such as `// `, `# `, `> ` or ` * `.
+fn prefix_len(line: &str) -> usize {
+  let indent = line.len() - line.trim_start().len();
+  let mut len = indent;
+  while let Some(marker) = COMMENT_MARKERS.iter().find(|marker| {
+    let rest = &line[len..];
+    rest.starts_with(*marker)
+      // A leading `*` is a bullet unless it continues an indented block comment
+      && (**marker != "*" || (indent > 0 && len == indent))
+      && rest[marker.len()..]
+        .chars()
+        .next()
+        .is_none_or(|c| c.is_whitespace() || marker.starts_with(c))
+  }) {
+    len += marker.len();
+    len = line.len() - line[len..].trim_start().len();
+  }
+  len
+}
+
+/// Length of a leading list bullet, such as `- `, `* ` or `1. `, including trailing whitespace.
+fn bullet_len(content: &str) -> usize {
+  let digits = content.len()
+    - content
+      .trim_start_matches(|c: char| c.is_ascii_digit())
+      .len();
+  let marker = match content[digits..].chars().next() {
+    Some('.' | ')') if digits > 0 => digits + 1,
+    Some('-' | '*' | '+') if digits == 0 => 1,
+    _ => return 0,
+  };
+  let rest = &content[marker..];
+  if rest.starts_with(char::is_whitespace) {
+    content.len() - rest.trim_start().len()
+  } else {
+    0
+  }
+}
+
+/// Splits on whitespace, keeping inline code spans intact.
+fn words(content: &str) -> Vec<&str> {
+  let mut words = Vec::new();
+  let mut start = None;
+  let mut code = None;
+  let mut chars = content.char_indices().peekable();
+  while let Some((i, c)) = chars.next() {
+    if c == '`' {
+      let mut ticks = 1;
+      while chars.next_if(|&(_, c)| c == '`').is_some() {
+        ticks += 1;
+      }
+      code = match code {
+        None => Some(ticks),
+        Some(n) if n == ticks => None,
+        code => code,
+      };
+      start.get_or_insert(i);
+    } else if c.is_whitespace() && code.is_none() {
+      words.extend(start.take().map(|start| &content[start..i]));
+    } else {
+      start.get_or_insert(i);
+    }
+  }
+  words.extend(start.map(|start| &content[start..]));
+  words
+}
+
+fn wrap(paragraph: &Paragraph, width: usize) -> Vec<String> {
+  let mut lines = Vec::new();
+  let mut line = paragraph.first_prefix.clone();
+  let mut empty = true;
+  for word in words(&paragraph.content) {
+    if !empty && line.chars().count() + 1 + word.chars().count() > width {
+      lines.push(line);
+      line = paragraph.rest_prefix.clone();
+      empty = true;
+    }
+    if !empty {
+      line.push(' ');
+    }
+    line.push_str(word);
+    empty = false;
+  }
+  lines.push(line);
+  lines
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use ropey::Rope;
+
+  fn reflow(text: &str, width: usize) -> String {
+    Reflow { width }
+      .transform(Rope::from_str(text).slice(..))
+      .unwrap()
+  }
+
+  #[test]
+  fn test_reflow_comment_prefix() {
+    assert_eq!(
+      reflow("// one two three four five six\n", 16),
+      "// one two three\n// four five six\n"
+    );
+    assert_eq!(
+      reflow("  # one two\n  # three four\n", 80),
+      "  # one two three four\n"
+    );
+  }
+
+  #[test]
+  fn test_reflow_paragraphs_and_bullets() {
+    assert_eq!(
+      reflow("> a b c d\n>\n- item one two\n- item three", 10),
+      "> a b c d\n>\n- item one\n  two\n- item\n  three"
+    );
+  }
+
+  #[test]
+  fn test_reflow_keeps_urls_and_code() {
+    assert_eq!(
+      reflow("see https://example.com/a/very/long/path and `a b c`", 12),
+      "see\nhttps://example.com/a/very/long/path\nand `a b c`"
+    );
+  }
+
+  #[test]
+  fn test_unwrap() {
+    assert_eq!(
+      Unwrap
+        .transform(Rope::from_str(" * one\n * two\n *\n * three\r\n").slice(..))
+        .unwrap(),
+      " * one two\n *\n * three\r\n"
+    );
+  }
+
+  #[test]
+  fn test_reflow_keeps_line_endings() {
+    assert_eq!(
+      reflow("a b\r\nc\n\nd e f\r\n", 3),
+      "a b\r\nc\n\nd e\r\nf\r\n"
+    );
+  }
+}
@@ -0,0 +1,204 @@
+use crate::{
+  r#trait::{CommandMeta, Text, Transform},
+  r#type::RegexReplace,
+};
+use regex::{Regex, RegexBuilder};
+use ropey::RopeSlice;
+use serde_json::{Value, from_value, json};
+use tap::prelude::*;
+use tower_lsp::{
+  jsonrpc::Error,
+  lsp_types::{CodeActionKind, ExecuteCommandParams, Range, TextEdit},
+};
+
+impl RegexReplace {
+  /// Flags follow the usual single letter spelling: `i` case-insensitive,
+  /// `m` multi-line anchors, `s` dot matches newline, `x` verbose, `U` lazy
+  /// by default. `g` is accepted and ignored since every match is replaced.
+  pub fn regex(&self) -> Result<Regex, String> {
+    let mut builder = RegexBuilder::new(&self.pattern);
+    for flag in self.flags.chars() {
+      match flag {
+        'i' => builder.case_insensitive(true),
+        'm' => builder.multi_line(true),
+        's' => builder.dot_matches_new_line(true),
+        'x' => builder.ignore_whitespace(true),
+        'U' => builder.swap_greed(true),
+        'g' => &mut builder,
+        _ => return Err(format!("Unknown regex flag {flag:?}")),
+      };
+    }
+    builder.build().map_err(|err| err.to_string())
+  }
+
+  /// One edit per match, trimmed down to the part that actually changes.
+  /// Matches whose expansion equals the matched text produce no edit.
+  fn edits(&self, text: &str) -> Option<Vec<(std::ops::Range<usize>, String)>> {
+    self
+      .regex()
+      .ok()?
+      .captures_iter(text)
+      .filter_map(|captures| {
+        let matched = captures.get(0)?;
+        let mut replacement = String::new();
+        captures.expand(&self.replacement, &mut replacement);
+        let old = matched.as_str();
+        let prefix = old
+          .char_indices()
+          .zip(replacement.chars())
+          .take_while(|((_, a), b)| a == b)
+          .last()
+          .map(|((idx, c), _)| idx + c.len_utf8())
+          .unwrap_or(0);
+        let suffix = old[prefix..]
+          .chars()
+          .rev()
+          .zip(replacement[prefix..].chars().rev())
+          .take_while(|(a, b)| a == b)
+          .map(|(c, _)| c.len_utf8())
+          .sum::<usize>();
+        (old != replacement).then(|| {
+          (
+            matched.start() + prefix..matched.end() - suffix,
+            replacement[prefix..replacement.len() - suffix].to_string(),
+          )
+        })
+      })
+      .collect::<Vec<_>>()
+      .pipe(Some)
+  }
+}
+
+impl TryFrom<&ExecuteCommandParams> for RegexReplace {
+  type Error = Error;
+
+  /// Options are the third command argument, after the URI and the range.
+  fn try_from(params: &ExecuteCommandParams) -> Result<Self, Self::Error> {
+    let replace = params
+      .arguments
+      .get(2)
+      .cloned()
+      .ok_or_else(|| Error::invalid_params("Missing regex replace options argument"))?
+      .pipe(from_value::<RegexReplace>)
+      .map_err(|err| {
+        Error::invalid_params(format!("Failed to convert regex replace options: {err}"))
+      })?;
+    replace
+      .regex()
+      .map_err(|err| Error::invalid_params(format!("Invalid regex: {err}")))?;
+    Ok(replace)
+  }
+}
+
+impl CommandMeta for RegexReplace {
+  fn command_name(&self) -> &'static str {
+    "text-language-server.regex-replace"
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    "Regex replace"
+  }
+}
+
+impl Transform for RegexReplace {
+  fn code_action_kind(&self) -> Vec<CodeActionKind> {
+    vec![CodeActionKind::REFACTOR_REWRITE, CodeActionKind::SOURCE]
+  }
+
+  fn code_action_condition(&self, source: RopeSlice, range: Range) -> bool {
+    self
+      .regex()
+      .is_ok_and(|regex| regex.is_match(&source.slice(source.range(range)).to_string()))
+  }
+
+  fn transform(&self, text: RopeSlice) -> Option<String> {
+    self
+      .regex()
+      .ok()?
+      .replace_all(&text.to_string(), self.replacement.as_str())
+      .into_owned()
+      .pipe(Some)
+  }
+
+  fn dry_run(&self, source: RopeSlice, range: Range) -> Option<Value> {
+    if !self.dry_run {
+      return None;
+    }
+    let matches = self
+      .regex()
+      .ok()?
+      .find_iter(&source.slice(source.range(range)).to_string())
+      .count();
+    Some(json!({ "matches": matches }))
+  }
+
+  fn text_edits(&self, source: RopeSlice, range: Range) -> Option<Vec<TextEdit>> {
+    let start = source.position(range.start);
+    self
+      .edits(&source.slice(source.range(range)).to_string())?
+      .into_iter()
+      .map(|(edit, new_text)| TextEdit {
+        range: source.lsp_range(start + edit.start..start + edit.end),
+        new_text,
+      })
+      .collect::<Vec<_>>()
+      .pipe(Some)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use ropey::Rope;
+  use tower_lsp::lsp_types::Position;
+
+  fn replace(pattern: &str, replacement: &str, flags: &str) -> RegexReplace {
+    RegexReplace {
+      pattern: pattern.to_string(),
+      replacement: replacement.to_string(),
+      flags: flags.to_string(),
+      dry_run: false,
+    }
+  }
+
+  #[test]
+  fn test_regex_replace_minimal_edits() {
+    let rope = Rope::from_str("let foo_bar = 1;\nlet foo_baz = 2;\n");
+    let source = rope.slice(..);
+    let edits = replace(r"foo_(\w+)", "foo_${1}_new", "")
+      .text_edits(source, source.range_full())
+      .unwrap();
+    assert_eq!(
+      edits,
+      vec![
+        TextEdit {
+          range: Range::new(Position::new(0, 11), Position::new(0, 11)),
+          new_text: "_new".to_string(),
+        },
+        TextEdit {
+          range: Range::new(Position::new(1, 11), Position::new(1, 11)),
+          new_text: "_new".to_string(),
+        },
+      ]
+    );
+    assert_eq!(
+      replace("FOO", "qux", "i").transform(source).as_deref(),
+      Some("let qux_bar = 1;\nlet qux_baz = 2;\n")
+    );
+  }
+
+  #[test]
+  fn test_regex_replace_dry_run_and_flags() {
+    let rope = Rope::from_str("a1 b2 c3");
+    let source = rope.slice(..);
+    let mut dry_run = replace(r"\d", "", "g");
+    assert_eq!(dry_run.dry_run(source, source.range_full()), None);
+    dry_run.dry_run = true;
+    assert_eq!(
+      dry_run.dry_run(source, source.range_full()),
+      Some(json!({ "matches": 3 }))
+    );
+    assert!(replace("a", "", "q").regex().is_err());
+    assert!(replace("(", "", "").regex().is_err());
+  }
+}
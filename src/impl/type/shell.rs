@@ -0,0 +1,153 @@
+use crate::{
+  r#trait::{CommandMeta, Text, Transform},
+  r#type::{ShellJoin, ShellQuote, ShellSplit, ShellUnquote},
+};
+use ropey::RopeSlice;
+use serde_json::{from_str, to_string};
+use tower_lsp::lsp_types::{CodeActionKind, Range};
+
+impl CommandMeta for ShellQuote {
+  fn command_name(&self) -> &'static str {
+    "text-language-server.shell-quote"
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    "Shell quote"
+  }
+}
+
+impl Transform for ShellQuote {
+  fn code_action_kind(&self) -> Vec<CodeActionKind> {
+    vec![CodeActionKind::REFACTOR_REWRITE, CodeActionKind::SOURCE]
+  }
+
+  fn code_action_condition(&self, _: RopeSlice, range: Range) -> bool {
+    range.start != range.end
+  }
+
+  fn transform(&self, text: RopeSlice) -> Option<String> {
+    Some(shell_words::quote(&text.to_string()).into_owned())
+  }
+}
+
+impl CommandMeta for ShellUnquote {
+  fn command_name(&self) -> &'static str {
+    "text-language-server.shell-unquote"
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    "Shell unquote"
+  }
+}
+
+impl Transform for ShellUnquote {
+  fn code_action_kind(&self) -> Vec<CodeActionKind> {
+    vec![CodeActionKind::REFACTOR_REWRITE, CodeActionKind::SOURCE]
+  }
+
+  fn code_action_condition(&self, source: RopeSlice, range: Range) -> bool {
+    let text = source.slice(source.range(range));
+    self
+      .transform(text)
+      .is_some_and(|word| word != text.to_string().trim())
+  }
+
+  fn transform(&self, text: RopeSlice) -> Option<String> {
+    let [word] = shell_words::split(&text.to_string())
+      .ok()?
+      .try_into()
+      .ok()?;
+    Some(word)
+  }
+}
+
+impl CommandMeta for ShellSplit {
+  fn command_name(&self) -> &'static str {
+    "text-language-server.shell-split"
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    "Shell command to argv JSON"
+  }
+}
+
+impl Transform for ShellSplit {
+  fn code_action_kind(&self) -> Vec<CodeActionKind> {
+    vec![CodeActionKind::REFACTOR_REWRITE, CodeActionKind::SOURCE]
+  }
+
+  fn code_action_condition(&self, source: RopeSlice, range: Range) -> bool {
+    shell_words::split(&source.slice(source.range(range)).to_string())
+      .is_ok_and(|argv| !argv.is_empty())
+  }
+
+  fn transform(&self, text: RopeSlice) -> Option<String> {
+    shell_words::split(&text.to_string())
+      .ok()
+      .and_then(|argv| to_string(&argv).ok())
+  }
+}
+
+impl CommandMeta for ShellJoin {
+  fn command_name(&self) -> &'static str {
+    "text-language-server.shell-join"
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    "Argv JSON to shell command"
+  }
+}
+
+impl Transform for ShellJoin {
+  fn code_action_kind(&self) -> Vec<CodeActionKind> {
+    vec![CodeActionKind::REFACTOR_REWRITE, CodeActionKind::SOURCE]
+  }
+
+  fn code_action_condition(&self, source: RopeSlice, range: Range) -> bool {
+    self.transform(source.slice(source.range(range))).is_some()
+  }
+
+  fn transform(&self, text: RopeSlice) -> Option<String> {
+    from_str::<Vec<String>>(&text.to_string())
+      .ok()
+      .filter(|argv| !argv.is_empty())
+      .map(shell_words::join)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use ropey::Rope;
+
+  fn apply(transform: &impl Transform, text: &str) -> Option<String> {
+    transform.transform(Rope::from_str(text).slice(..))
+  }
+
+  #[test]
+  fn test_shell_quote_round_trip() {
+    let quoted = apply(&ShellQuote, "it's a $HOME \"test\"").unwrap();
+    assert_eq!(quoted, r#"'it'\''s a $HOME "test"'"#);
+    assert_eq!(
+      apply(&ShellUnquote, &quoted).as_deref(),
+      Some("it's a $HOME \"test\"")
+    );
+    assert_eq!(apply(&ShellUnquote, "two words"), None);
+  }
+
+  #[test]
+  fn test_shell_split_and_join() {
+    let command = r#"docker run -e "A=b c" --name=x\ y 'img:1'"#;
+    let argv = apply(&ShellSplit, command).unwrap();
+    assert_eq!(
+      argv,
+      r#"["docker","run","-e","A=b c","--name=x y","img:1"]"#
+    );
+    assert_eq!(
+      apply(&ShellJoin, &argv).as_deref(),
+      Some("docker run -e 'A=b c' '--name=x y' img:1")
+    );
+    assert_eq!(apply(&ShellSplit, "echo 'unterminated"), None);
+    assert_eq!(apply(&ShellJoin, r#"["a", 1]"#), None);
+  }
+}
@@ -1,9 +1,9 @@
 use crate::{
-  r#trait::{CommandMeta, Transform},
+  r#trait::{CommandMeta, Text, Transform},
   r#type::Source,
 };
 use ropey::RopeSlice;
-use tower_lsp::lsp_types::{CodeActionKind, Range};
+use tower_lsp::lsp_types::{CodeActionKind, Range, TextEdit};
 
 impl<T: CommandMeta> CommandMeta for Source<T> {
   fn command_name(&self) -> &'static str {
@@ -27,4 +27,16 @@ impl<T: Transform> Transform for Source<T> {
   fn transform(&self, text: RopeSlice) -> Option<String> {
     self.0.transform(text)
   }
+
+  fn validate(&self, source: RopeSlice, range: Range) -> Result<(), String> {
+    self.0.validate(source, range)
+  }
+
+  fn code_action_range(&self, source: RopeSlice, _: Range) -> Range {
+    source.range_full()
+  }
+
+  fn text_edits(&self, source: RopeSlice, range: Range) -> Option<Vec<TextEdit>> {
+    self.0.text_edits(source, range)
+  }
 }
@@ -0,0 +1,234 @@
+use crate::{
+  r#trait::{CommandMeta, Text, Transform},
+  r#type::{Config, SqlFormat, SqlKeywordCase},
+};
+use ropey::{LineType, RopeSlice};
+use sqlformat::{FormatOptions, Indent, QueryParams};
+use tap::prelude::*;
+use tower_lsp::lsp_types::{CodeActionKind, Range, TextEdit};
+
+const STATEMENTS: [&str; 12] = [
+  "SELECT", "WITH", "INSERT", "UPDATE", "DELETE", "MERGE", "CREATE", "ALTER", "DROP", "EXPLAIN",
+  "VALUES", "UPSERT",
+];
+
+impl SqlFormat {
+  fn format(&self, sql: &str, uppercase: bool) -> String {
+    sqlformat::format(
+      sql,
+      &QueryParams::None,
+      FormatOptions {
+        indent: Indent::Spaces(u8::try_from(self.indent_width).unwrap_or(u8::MAX)),
+        uppercase,
+        lines_between_queries: 1,
+      },
+    )
+  }
+
+  /// sqlformat can only upper-case keywords, so lower case is recovered by formatting a
+  /// lower-cased copy with upper-cased keywords: the only upper-case ASCII left in it marks
+  /// keyword characters, which are then lower-cased in the case-preserving output. Both
+  /// renderings share a layout because token kinds and lengths are the same.
+  fn lowercase_keywords(&self, sql: &str) -> String {
+    let preserved = self.format(sql, false);
+    let marked = self.format(&sql.to_lowercase(), true);
+    if preserved.chars().count() != marked.chars().count() {
+      return preserved;
+    }
+    preserved
+      .chars()
+      .zip(marked.chars())
+      .map(|(c, mark)| {
+        if mark.is_ascii_uppercase() {
+          c.to_ascii_lowercase()
+        } else {
+          c
+        }
+      })
+      .collect()
+  }
+}
+
+impl From<&Config> for SqlFormat {
+  fn from(config: &Config) -> Self {
+    Self {
+      indent_width: config.indent_width,
+      keyword_case: config.sql_keyword_case,
+    }
+  }
+}
+
+impl CommandMeta for SqlFormat {
+  fn command_name(&self) -> &'static str {
+    "text-language-server.sql-format"
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    "Format SQL"
+  }
+}
+
+impl Transform for SqlFormat {
+  fn code_action_kind(&self) -> Vec<CodeActionKind> {
+    vec![CodeActionKind::QUICKFIX, CodeActionKind::SOURCE]
+  }
+
+  fn code_action_condition(&self, source: RopeSlice, range: Range) -> bool {
+    let text = source.slice(source.range(range));
+    let sql = text.to_string();
+    sql
+      .trim_start()
+      .trim_start_matches('(')
+      .split(|c: char| !c.is_ascii_alphabetic())
+      .next()
+      .is_some_and(|keyword| {
+        STATEMENTS
+          .iter()
+          .any(|statement| statement.eq_ignore_ascii_case(keyword))
+      })
+      && self
+        .transform(text)
+        .is_some_and(|formatted| formatted != sql.trim())
+  }
+
+  fn transform(&self, text: RopeSlice) -> Option<String> {
+    let sql = text.to_string();
+    match self.keyword_case {
+      SqlKeywordCase::Upper => self.format(&sql, true),
+      SqlKeywordCase::Lower => self.lowercase_keywords(&sql),
+      SqlKeywordCase::Preserve => self.format(&sql, false),
+    }
+    .pipe(Some)
+  }
+
+  /// Continuation lines pick up the indentation and line ending of the line the selection starts
+  /// on, so SQL embedded in a string literal of another language stays aligned with its
+  /// surroundings.
+  fn text_edits(&self, source: RopeSlice, range: Range) -> Option<Vec<TextEdit>> {
+    let line = source
+      .line(range.start.line as usize, LineType::LF_CR)
+      .to_string();
+    let indent = line
+      .chars()
+      .take_while(|c| *c == ' ' || *c == '\t')
+      .collect::<String>();
+    let line_ending = if line.ends_with("\r\n") {
+      "\r\n"
+    } else if line.ends_with('\r') {
+      "\r"
+    } else {
+      "\n"
+    };
+    self
+      .transform(source.slice(source.range(range)))?
+      .lines()
+      .enumerate()
+      .map(|(idx, line)| {
+        if idx == 0 || line.is_empty() {
+          line.to_string()
+        } else {
+          format!("{indent}{line}")
+        }
+      })
+      .collect::<Vec<_>>()
+      .join(line_ending)
+      .pipe(|new_text| Some(vec![TextEdit { range, new_text }]))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use ropey::Rope;
+  use tower_lsp::lsp_types::Position;
+
+  const SQL: &str = "select u.id, u.name, count(o.id) as orders from users u left join orders o on o.user_id = u.id where u.active = 'select' group by u.id, u.name";
+
+  fn format(keyword_case: SqlKeywordCase) -> String {
+    SqlFormat {
+      indent_width: 2,
+      keyword_case,
+    }
+    .transform(Rope::from_str(SQL).slice(..))
+    .unwrap()
+  }
+
+  #[test]
+  fn test_sql_format_keyword_case() {
+    assert_eq!(
+      format(SqlKeywordCase::Upper),
+      [
+        "SELECT",
+        "  u.id,",
+        "  u.name,",
+        "  count(o.id) AS orders",
+        "FROM",
+        "  users u",
+        "  LEFT JOIN orders o ON o.user_id = u.id",
+        "WHERE",
+        "  u.active = 'select'",
+        "GROUP BY",
+        "  u.id,",
+        "  u.name",
+      ]
+      .join("\n")
+    );
+    assert_eq!(
+      format(SqlKeywordCase::Lower),
+      format(SqlKeywordCase::Upper)
+        .replace("SELECT", "select")
+        .replace("AS", "as")
+        .replace("FROM", "from")
+        .replace("LEFT JOIN", "left join")
+        .replace("ON", "on")
+        .replace("WHERE", "where")
+        .replace("GROUP BY", "group by")
+    );
+    assert_eq!(
+      SqlFormat {
+        indent_width: 4,
+        keyword_case: SqlKeywordCase::Lower,
+      }
+      .transform(Rope::from_str("SELECT 'A' FROM T").slice(..))
+      .as_deref(),
+      Some("select\n    'A'\nfrom\n    T")
+    );
+  }
+
+  #[test]
+  fn test_sql_format_embedded_indent() {
+    let rope = Rope::from_str("    query = \"select a, b from t\"\n");
+    let source = rope.slice(..);
+    let range = Range::new(Position::new(0, 13), Position::new(0, 31));
+    let format = SqlFormat {
+      indent_width: 2,
+      keyword_case: SqlKeywordCase::Upper,
+    };
+    assert!(format.code_action_condition(source, range));
+    assert_eq!(
+      format.text_edits(source, range).unwrap()[0].new_text,
+      "SELECT\n      a,\n      b\n    FROM\n      t"
+    );
+    let rope = Rope::from_str("select a from t\r\n");
+    let source = rope.slice(..);
+    assert_eq!(
+      format
+        .text_edits(
+          source,
+          Range::new(Position::new(0, 0), Position::new(0, 15))
+        )
+        .unwrap()[0]
+        .new_text,
+      "SELECT\r\n  a\r\nFROM\r\n  t"
+    );
+    assert_eq!(
+      SqlFormat {
+        indent_width: 300,
+        keyword_case: SqlKeywordCase::Upper,
+      }
+      .transform(Rope::from_str("select a").slice(..))
+      .map(|sql| sql.lines().nth(1).unwrap().len()),
+      Some(256)
+    );
+  }
+}
@@ -0,0 +1,508 @@
+use crate::{
+  r#trait::{CommandMeta, Text, Transform},
+  r#type::StringLiteral,
+};
+use regex::Regex;
+use ropey::{LineType, RopeSlice};
+use std::{ops, sync::LazyLock};
+use tower_lsp::lsp_types::{CodeActionKind, Range, TextEdit};
+
+static YAML_HEADER: LazyLock<Regex> =
+  LazyLock::new(|| Regex::new(r"^\|(?:([1-9])?([+-])?|([+-])([1-9]))$").unwrap());
+static HEREDOC_HEADER: LazyLock<Regex> =
+  LazyLock::new(|| Regex::new(r#"^<<(-)?\s*(['"]?)([A-Za-z_][A-Za-z0-9_]*)['"]?$"#).unwrap());
+
+/// Python's escapes that can appear in text; unknown escapes are kept as written, as Python does.
+fn unescape_python(text: &str) -> String {
+  let mut unescaped = String::with_capacity(text.len());
+  let mut chars = text.chars();
+  while let Some(c) = chars.next() {
+    if c != '\\' {
+      unescaped.push(c);
+      continue;
+    }
+    match chars.next() {
+      Some('n') => unescaped.push('\n'),
+      Some('t') => unescaped.push('\t'),
+      Some('r') => unescaped.push('\r'),
+      Some('\n') => {}
+      Some(c @ ('\\' | '\'' | '"')) => unescaped.push(c),
+      Some(c) => unescaped.extend(['\\', c]),
+      None => unescaped.push('\\'),
+    }
+  }
+  unescaped
+}
+
+fn parse_rust(text: &str) -> Option<String> {
+  let rest = text.strip_prefix('r')?;
+  let hashes = &rest[..rest.len() - rest.trim_start_matches('#').len()];
+  let content = rest
+    .strip_prefix(hashes)?
+    .strip_prefix('"')?
+    .strip_suffix(hashes)?
+    .strip_suffix('"')?;
+  Some(content.to_string())
+}
+
+fn parse_python(text: &str) -> Option<String> {
+  let (raw, rest) = match text.strip_prefix(['r', 'R']) {
+    Some(rest) => (true, rest),
+    None => (false, text),
+  };
+  let quotes = ["\"\"\"", "'''"]
+    .into_iter()
+    .find(|quotes| rest.starts_with(quotes))?;
+  let content = rest.strip_prefix(quotes)?.strip_suffix(quotes)?;
+  Some(if raw {
+    content.to_string()
+  } else {
+    unescape_python(content)
+  })
+}
+
+/// Backslashes are kept as written, which is exact for Go raw strings and for template
+/// literals without escapes. Template literals with substitutions are not plain text.
+fn parse_backtick(text: &str) -> Option<String> {
+  let text = text.strip_prefix("String.raw").unwrap_or(text);
+  let content = text.strip_prefix('`')?.strip_suffix('`')?;
+  (!content.contains('`') && !content.contains("${")).then(|| content.to_string())
+}
+
+/// Also tells whether the scalar keeps its trailing blank lines, which then belong to it.
+fn parse_yaml(text: &str) -> Option<(String, bool)> {
+  let (header, body) = text.split_once('\n').unwrap_or((text, ""));
+  let captures = YAML_HEADER.captures(header.trim_end())?;
+  let lines = body.lines().collect::<Vec<_>>();
+  let indent = match captures.get(1).or(captures.get(4)) {
+    Some(indicator) => indicator.as_str().parse().ok()?,
+    None => lines
+      .iter()
+      .find(|line| !line.trim().is_empty())
+      .map(|line| line.len() - line.trim_start_matches(' ').len())
+      .unwrap_or(0),
+  };
+  let lines = lines
+    .iter()
+    .map(|line| line.get(indent..).unwrap_or(""))
+    .collect::<Vec<_>>();
+  let trailing = lines
+    .iter()
+    .rev()
+    .take_while(|line| line.is_empty())
+    .count();
+  let mut content = lines[..lines.len() - trailing].join("\n");
+  let chomp = captures
+    .get(2)
+    .or(captures.get(3))
+    .map(|chomp| chomp.as_str());
+  match chomp {
+    Some("-") => {}
+    Some(_) => content.push_str(&"\n".repeat(trailing + 1)),
+    None => content.push('\n'),
+  }
+  Some((content, chomp == Some("+")))
+}
+
+fn parse_heredoc(text: &str) -> Option<String> {
+  let mut lines = text.lines();
+  let captures = HEREDOC_HEADER.captures(lines.next()?.trim_end())?;
+  let strip_tabs = captures.get(1).is_some();
+  let delimiter = &captures[3];
+  let mut content = String::new();
+  for line in lines {
+    let line = if strip_tabs {
+      line.trim_start_matches('\t')
+    } else {
+      line
+    };
+    if line.trim_end() == delimiter {
+      return Some(content);
+    }
+    content.push_str(line);
+    content.push('\n');
+  }
+  None
+}
+
+/// The byte range and text of a literal in any of the supported syntaxes, delimiters included.
+/// Whitespace around the literal is not part of it, except for the trailing blank lines of a
+/// YAML block scalar that keeps them.
+fn parse(text: &str) -> Option<(ops::Range<usize>, String)> {
+  let start = text.len() - text.trim_start().len();
+  let end = text.trim_end().len().max(start);
+  let literal = &text[start..end];
+  // Python first, as `r"""…"""` would also read as a Rust raw string of quotes.
+  if let Some(content) = parse_python(literal)
+    .or_else(|| parse_rust(literal))
+    .or_else(|| parse_backtick(literal))
+  {
+    return Some((start..end, content));
+  }
+  if let Some((content, keep)) = parse_yaml(&text[start..]) {
+    return Some((start..if keep { text.len() } else { end }, content));
+  }
+  parse_heredoc(literal).map(|content| (start..end, content))
+}
+
+/// How far around the selection an enclosing literal is looked for.
+const CONTEXT_LINES: usize = 50;
+
+/// The byte length of a literal opening at the start of `text`, up to its closing delimiter.
+fn literal_len(text: &str) -> Option<usize> {
+  let line_end = text.find('\n').unwrap_or(text.len());
+  let header = text[..line_end].trim_end();
+  let body = text.get(line_end + 1..).unwrap_or_default();
+  if let Some(captures) = YAML_HEADER.captures(header) {
+    let keep = captures
+      .get(2)
+      .or(captures.get(3))
+      .map(|chomp| chomp.as_str())
+      == Some("+");
+    let (mut len, mut start) = (line_end, line_end + 1);
+    let mut indent = None;
+    for line in body.split_inclusive('\n') {
+      let content = line.trim_end_matches(['\r', '\n']);
+      if content.trim().is_empty() {
+        if keep {
+          len = start + line.len();
+        }
+      } else {
+        let width = content.len() - content.trim_start_matches(' ').len();
+        if width == 0 || width < *indent.get_or_insert(width) {
+          break;
+        }
+        len = start + content.len();
+      }
+      start += line.len();
+    }
+    return Some(len);
+  }
+  if let Some(captures) = HEREDOC_HEADER.captures(header) {
+    let mut start = line_end + 1;
+    for line in body.split_inclusive('\n') {
+      let content = line.trim_end_matches(['\r', '\n']);
+      let stripped = if captures.get(1).is_some() {
+        content.trim_start_matches('\t')
+      } else {
+        content
+      };
+      if stripped.trim_end() == &captures[3] {
+        return Some(start + content.len());
+      }
+      start += line.len();
+    }
+    return None;
+  }
+  if let Some(rest) = text
+    .strip_prefix("String.raw`")
+    .or_else(|| text.strip_prefix('`'))
+  {
+    return Some(text.len() - rest.len() + rest.find('`')? + 1);
+  }
+  let unprefixed = text.strip_prefix(['r', 'R']).unwrap_or(text);
+  if let Some(quotes) = ["\"\"\"", "\'\'\'"]
+    .into_iter()
+    .find(|quotes| unprefixed.starts_with(quotes))
+  {
+    let rest = &unprefixed[3..];
+    return Some(text.len() - rest.len() + rest.find(quotes)? + 3);
+  }
+  let rest = text.strip_prefix('r')?;
+  let hashes = &rest[..rest.len() - rest.trim_start_matches('#').len()];
+  let rest = rest.strip_prefix(hashes)?.strip_prefix('"')?;
+  Some(text.len() - rest.len() + rest.find(&format!("\"{hashes}"))? + 1 + hashes.len())
+}
+
+/// The literal the selection is in, when the selection isn't a literal itself: the nearest
+/// opening delimiter before the selection whose literal reaches past its end.
+fn enclosing(source: RopeSlice, range: Range) -> Option<Range> {
+  let selection = source.range(range);
+  if parse(&source.slice(selection.clone()).to_string()).is_some() {
+    return Some(range);
+  }
+  let first = (range.start.line as usize).saturating_sub(CONTEXT_LINES);
+  let last = (range.end.line as usize + CONTEXT_LINES + 1).min(source.len_lines(LineType::LF_CR));
+  let offset = source.line_to_byte_idx(first, LineType::LF_CR);
+  let window = source
+    .slice(offset..source.line_to_byte_idx(last, LineType::LF_CR))
+    .to_string();
+  let (start, end) = (selection.start - offset, selection.end - offset);
+  // The cursor may sit on the opening delimiter itself.
+  let at_cursor = window[start..].chars().next().map_or(0, char::len_utf8);
+  window[..start + at_cursor]
+    .char_indices()
+    .rev()
+    .filter(|(idx, c)| {
+      let word = window[..*idx]
+        .chars()
+        .next_back()
+        .is_some_and(|c| c.is_alphanumeric() || c == '_');
+      match c {
+        'r' | 'R' | 'S' => !word,
+        // A backtick after an odd number of them closes a literal rather than opening one.
+        '`' => {
+          window[..*idx].matches('`').count().is_multiple_of(2)
+            && !window[..*idx].ends_with("String.raw")
+        }
+        // Quotes after a raw prefix are found from the prefix.
+        '"' | '\'' => !window[..*idx].ends_with(['r', 'R']),
+        '|' | '<' => true,
+        _ => false,
+      }
+    })
+    .find_map(|(idx, _)| {
+      let len = literal_len(&window[idx..]).filter(|len| idx + len >= end)?;
+      let (span, _) = parse(&window[idx..idx + len])?;
+      Some(source.lsp_range(offset + idx + span.start..offset + idx + span.end))
+    })
+}
+
+impl StringLiteral {
+  /// Writes `content` as a literal with delimiters that need no escaping inside it, or `None`
+  /// where the syntax has no such delimiter. YAML lines are indented two spaces past `indent`.
+  fn render(&self, content: &str, indent: &str) -> Option<String> {
+    match self {
+      StringLiteral::Rust => {
+        let hashes = (0..)
+          .map(|count| "#".repeat(count))
+          .find(|hashes| !content.contains(&format!("\"{hashes}")))?;
+        Some(format!("r{hashes}\"{content}\"{hashes}"))
+      }
+      StringLiteral::Python => {
+        let quotes = ["\"\"\"", "'''"].into_iter().find(|quotes| {
+          !content.contains(quotes) && !content.ends_with(&quotes[..1]) && !content.ends_with('\\')
+        })?;
+        let prefix = if content.contains('\\') { "r" } else { "" };
+        Some(format!("{prefix}{quotes}{content}{quotes}"))
+      }
+      StringLiteral::JavaScript if content.contains('`') || content.contains("${") => None,
+      StringLiteral::JavaScript if content.contains('\\') => Some(format!("String.raw`{content}`")),
+      StringLiteral::JavaScript => Some(format!("`{content}`")),
+      StringLiteral::Go => (!content.contains('`')).then(|| format!("`{content}`")),
+      StringLiteral::Yaml => {
+        let body = content.trim_end_matches('\n');
+        let trailing = content.len() - body.len();
+        let mut yaml = String::from("|");
+        if body.starts_with(' ') {
+          yaml.push('2');
+        }
+        match trailing {
+          0 => yaml.push('-'),
+          1 => {}
+          _ => yaml.push('+'),
+        }
+        for line in body.split('\n') {
+          yaml.push('\n');
+          if !line.is_empty() {
+            yaml.push_str(indent);
+            yaml.push_str("  ");
+            yaml.push_str(line);
+          }
+        }
+        yaml.push_str(&"\n".repeat(trailing.saturating_sub(1)));
+        Some(yaml)
+      }
+      StringLiteral::Heredoc => {
+        let delimiter = (0..)
+          .map(|count| match count {
+            0 => String::from("EOF"),
+            count => format!("EOF{count}"),
+          })
+          .find(|delimiter| content.lines().all(|line| line != delimiter))?;
+        let newline = if content.ends_with('\n') { "" } else { "\n" };
+        Some(format!("<<'{delimiter}'\n{content}{newline}{delimiter}"))
+      }
+    }
+  }
+}
+
+impl StringLiteral {
+  /// Rewrites the literal in `text`, keeping whatever surrounds it.
+  fn convert(&self, text: &str, indent: &str) -> Option<String> {
+    let (span, content) = parse(text)?;
+    Some(format!(
+      "{}{}{}",
+      &text[..span.start],
+      self.render(&content, indent)?,
+      &text[span.end..]
+    ))
+  }
+}
+
+impl CommandMeta for StringLiteral {
+  fn command_name(&self) -> &'static str {
+    match self {
+      StringLiteral::Rust => "text-language-server.string-literal-rust",
+      StringLiteral::Python => "text-language-server.string-literal-python",
+      StringLiteral::JavaScript => "text-language-server.string-literal-javascript",
+      StringLiteral::Go => "text-language-server.string-literal-go",
+      StringLiteral::Yaml => "text-language-server.string-literal-yaml",
+      StringLiteral::Heredoc => "text-language-server.string-literal-heredoc",
+    }
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    match self {
+      StringLiteral::Rust => "Convert to Rust raw string",
+      StringLiteral::Python => "Convert to Python triple-quoted string",
+      StringLiteral::JavaScript => "Convert to JavaScript template literal",
+      StringLiteral::Go => "Convert to Go raw string",
+      StringLiteral::Yaml => "Convert to YAML block scalar",
+      StringLiteral::Heredoc => "Convert to heredoc",
+    }
+  }
+}
+
+impl Transform for StringLiteral {
+  fn code_action_kind(&self) -> Vec<CodeActionKind> {
+    vec![CodeActionKind::QUICKFIX, CodeActionKind::SOURCE]
+  }
+
+  fn code_action_condition(&self, source: RopeSlice, range: Range) -> bool {
+    enclosing(source, range).is_some_and(|range| {
+      let text = source.slice(source.range(range));
+      self.transform(text).is_some_and(|literal| literal != text)
+    })
+  }
+
+  /// A selection inside a literal, or just the cursor, stands for the whole literal.
+  fn code_action_range(&self, source: RopeSlice, range: Range) -> Range {
+    enclosing(source, range).unwrap_or(range)
+  }
+
+  fn transform(&self, text: RopeSlice) -> Option<String> {
+    self.convert(&text.to_string(), "")
+  }
+
+  /// YAML block scalars are indented relative to the line the selection starts on.
+  fn text_edits(&self, source: RopeSlice, range: Range) -> Option<Vec<TextEdit>> {
+    let line = source
+      .line(range.start.line as usize, LineType::LF_CR)
+      .to_string();
+    let indent = &line[..line.len() - line.trim_start().len()];
+    Some(vec![TextEdit {
+      range,
+      new_text: self.convert(&source.slice(source.range(range)).to_string(), indent)?,
+    }])
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use ropey::Rope;
+  use strum::IntoEnumIterator;
+  use tower_lsp::lsp_types::Position;
+
+  fn content(text: &str) -> Option<String> {
+    parse(text).map(|(_, content)| content)
+  }
+
+  const CONTENT: &str = "SELECT \"id\"\n  FROM t -- \\d\n";
+
+  #[test]
+  fn test_string_literal_round_trip() {
+    let literals = StringLiteral::iter()
+      .map(|literal| literal.render(CONTENT, "").unwrap())
+      .collect::<Vec<_>>();
+    assert_eq!(
+      literals,
+      vec![
+        "r#\"SELECT \"id\"\n  FROM t -- \\d\n\"#",
+        "r\"\"\"SELECT \"id\"\n  FROM t -- \\d\n\"\"\"",
+        "String.raw`SELECT \"id\"\n  FROM t -- \\d\n`",
+        "`SELECT \"id\"\n  FROM t -- \\d\n`",
+        "|\n  SELECT \"id\"\n    FROM t -- \\d",
+        "<<'EOF'\nSELECT \"id\"\n  FROM t -- \\d\nEOF",
+      ]
+    );
+    for literal in literals {
+      assert_eq!(content(&literal).as_deref(), Some(CONTENT), "{literal}");
+    }
+    assert_eq!(content("'''it\\'s\\n'''").as_deref(), Some("it's\n"),);
+    assert_eq!(content("<<-\"END\"\n\t\ta\n\tEND").as_deref(), Some("a\n"));
+    assert_eq!(content("`${name}`"), None);
+    assert_eq!(
+      StringLiteral::Rust.render("\"#", "").as_deref(),
+      Some("r##\"\"#\"##")
+    );
+    assert_eq!(StringLiteral::Go.render("a`b", ""), None);
+    assert_eq!(
+      StringLiteral::Heredoc.render("EOF\n", "").as_deref(),
+      Some("<<'EOF1'\nEOF\nEOF1")
+    );
+  }
+
+  #[test]
+  fn test_string_literal_enclosing() {
+    let rope = Rope::from_str(
+      "let a = r#\"x\"# + `ü`;\nlet sql = r\"\"\"\nSELECT 1\n\"\"\"\nrun: |\n  echo hi\n  echo bye\nnext: 1\ncat <<EOF\nhi\nEOF\n",
+    );
+    let source = rope.slice(..);
+    let at = |line, character| {
+      Range::new(
+        Position::new(line, character),
+        Position::new(line, character),
+      )
+    };
+    let cases = [
+      (
+        at(0, 12),
+        Some(Range::new(Position::new(0, 8), Position::new(0, 14))),
+      ),
+      (
+        at(0, 18),
+        Some(Range::new(Position::new(0, 17), Position::new(0, 20))),
+      ),
+      (at(0, 16), None),
+      (
+        at(2, 3),
+        Some(Range::new(Position::new(1, 10), Position::new(3, 3))),
+      ),
+      (
+        at(5, 4),
+        Some(Range::new(Position::new(4, 5), Position::new(6, 10))),
+      ),
+      (at(7, 2), None),
+      (
+        at(9, 1),
+        Some(Range::new(Position::new(8, 4), Position::new(10, 3))),
+      ),
+    ];
+    for (range, expected) in cases {
+      assert_eq!(enclosing(source, range), expected, "{range:?}");
+    }
+    assert!(StringLiteral::Go.code_action_condition(source, at(2, 3)));
+  }
+
+  #[test]
+  fn test_string_literal_yaml() {
+    assert_eq!(content("|-\n    a\n\n    b\n").as_deref(), Some("a\n\nb"));
+    assert_eq!(content("|+\n  a\n\n").as_deref(), Some("a\n\n"));
+    assert_eq!(
+      StringLiteral::Go.convert("x = |+\n  a\n\n", "").as_deref(),
+      None
+    );
+    assert_eq!(
+      StringLiteral::Go.convert("  |+\n  a\n\n", "").as_deref(),
+      Some("  `a\n\n`")
+    );
+    assert_eq!(
+      StringLiteral::Go.convert(" r\"a\"\n", "").as_deref(),
+      Some(" `a`\n")
+    );
+    assert_eq!(content("|2\n   a\n  b").as_deref(), Some(" a\nb\n"));
+    assert_eq!(
+      StringLiteral::Yaml.render(" a\nb", "").as_deref(),
+      Some("|2-\n   a\n  b")
+    );
+    let rope = Rope::from_str("jobs:\n  run: `echo hi\necho bye`\n");
+    let source = rope.slice(..);
+    let range = Range::new(Position::new(1, 7), Position::new(2, 9));
+    assert_eq!(
+      StringLiteral::Yaml.text_edits(source, range).unwrap()[0].new_text,
+      "|-\n    echo hi\n    echo bye"
+    );
+  }
+}
@@ -0,0 +1,229 @@
+use crate::{
+  r#trait::{CommandMeta, Text, Transform},
+  r#type::{Config, NormalizeTimestamps},
+};
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDateTime, SecondsFormat, Utc};
+use chrono_tz::Tz;
+use regex::Regex;
+use ropey::RopeSlice;
+use std::{fmt::Write, ops, sync::LazyLock};
+use tower_lsp::lsp_types::{CodeActionKind, Range, TextEdit};
+
+const MONTH: &str = "(?:Jan|Feb|Mar|Apr|May|Jun|Jul|Aug|Sep|Oct|Nov|Dec)";
+
+/// ISO 8601, Common Log Format, syslog and epoch seconds, tried in that order at each position.
+static TIMESTAMP: LazyLock<Regex> = LazyLock::new(|| {
+  Regex::new(&format!(
+    concat!(
+      r"(?P<iso>\b\d{{4}}-\d{{2}}-\d{{2}}[T ]\d{{2}}:\d{{2}}:\d{{2}}(?:[.,]\d{{1,9}})?(?:Z|[+-]\d{{2}}:?\d{{2}})?)",
+      r"|(?P<clf>\b\d{{2}}/{month}/\d{{4}}:\d{{2}}:\d{{2}}:\d{{2}} [+-]\d{{4}})",
+      r"|(?P<syslog>\b{month} [ \d]\d \d{{2}}:\d{{2}}:\d{{2}}\b)",
+      r"|(?P<epoch>\b\d{{10}}(?:\.\d{{1,9}})?\b)",
+    ),
+    month = MONTH
+  ))
+  .unwrap()
+});
+
+impl NormalizeTimestamps {
+  /// Timestamps without an offset are taken to be UTC.
+  fn parse(&self, captures: &regex::Captures) -> Option<DateTime<FixedOffset>> {
+    let utc = |naive: NaiveDateTime| naive.and_utc().fixed_offset();
+    if let Some(iso) = captures.name("iso") {
+      let iso = iso.as_str().replacen(' ', "T", 1).replace(',', ".");
+      let offset = iso
+        .rfind(['+', '-'])
+        .filter(|idx| *idx > 10)
+        .unwrap_or(iso.len());
+      return match &iso[offset..] {
+        "" if iso.ends_with('Z') => DateTime::parse_from_rfc3339(&iso).ok(),
+        "" => NaiveDateTime::parse_from_str(&iso, "%Y-%m-%dT%H:%M:%S%.f")
+          .ok()
+          .map(utc),
+        zone => DateTime::parse_from_str(
+          &format!("{}{}", &iso[..offset], zone.replace(':', "")),
+          "%Y-%m-%dT%H:%M:%S%.f%z",
+        )
+        .ok(),
+      };
+    }
+    if let Some(clf) = captures.name("clf") {
+      return DateTime::parse_from_str(clf.as_str(), "%d/%b/%Y:%H:%M:%S %z").ok();
+    }
+    if let Some(syslog) = captures.name("syslog") {
+      return NaiveDateTime::parse_from_str(
+        &format!("{} {}", self.year, syslog.as_str()),
+        "%Y %b %e %H:%M:%S",
+      )
+      .ok()
+      .map(utc);
+    }
+    let epoch = captures.name("epoch")?.as_str();
+    let (secs, fraction) = epoch.split_once('.').unwrap_or((epoch, ""));
+    let nanos = format!("{fraction:0<9}").parse().ok()?;
+    DateTime::<Utc>::from_timestamp(secs.parse().ok()?, nanos)
+      .map(|datetime| datetime.fixed_offset())
+  }
+
+  fn render(&self, datetime: DateTime<FixedOffset>, zone: Tz) -> Option<String> {
+    let datetime = datetime.with_timezone(&zone);
+    if self.format == "rfc3339" {
+      return Some(datetime.to_rfc3339_opts(SecondsFormat::AutoSi, zone == Tz::UTC));
+    }
+    // Writing rather than `to_string` turns an invalid format into an error instead of a panic.
+    let mut rendered = String::new();
+    write!(rendered, "{}", datetime.format(&self.format)).ok()?;
+    Some(rendered)
+  }
+
+  fn zone(&self) -> Result<Tz, String> {
+    self
+      .zone
+      .parse::<Tz>()
+      .map_err(|_| format!("Unknown time zone {:?}", self.zone))
+  }
+
+  /// Byte range and replacement of every timestamp that changes.
+  fn rewrites(&self, text: &str) -> Option<Vec<(ops::Range<usize>, String)>> {
+    let zone = self.zone().ok()?;
+    TIMESTAMP
+      .captures_iter(text)
+      .filter_map(|captures| {
+        let matched = captures.get(0)?;
+        let rendered = self.render(self.parse(&captures)?, zone)?;
+        (rendered != matched.as_str()).then(|| (matched.range(), rendered))
+      })
+      .collect::<Vec<_>>()
+      .into()
+  }
+}
+
+impl From<&Config> for NormalizeTimestamps {
+  fn from(config: &Config) -> Self {
+    Self {
+      format: config.timestamp_format.clone(),
+      zone: config.timestamp_zone.clone(),
+      year: Utc::now().year(),
+    }
+  }
+}
+
+impl CommandMeta for NormalizeTimestamps {
+  fn command_name(&self) -> &'static str {
+    "text-language-server.normalize-timestamps"
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    "Normalize timestamps"
+  }
+}
+
+impl Transform for NormalizeTimestamps {
+  fn code_action_kind(&self) -> Vec<CodeActionKind> {
+    vec![CodeActionKind::QUICKFIX, CodeActionKind::SOURCE]
+  }
+
+  fn code_action_condition(&self, source: RopeSlice, range: Range) -> bool {
+    self
+      .rewrites(&source.slice(source.range(range)).to_string())
+      .is_some_and(|rewrites| !rewrites.is_empty())
+  }
+
+  fn transform(&self, text: RopeSlice) -> Option<String> {
+    let mut text = text.to_string();
+    for (range, rendered) in self.rewrites(&text)?.into_iter().rev() {
+      text.replace_range(range, &rendered);
+    }
+    Some(text)
+  }
+
+  fn validate(&self, _: RopeSlice, _: Range) -> Result<(), String> {
+    self.zone()?;
+    self
+      .render(DateTime::UNIX_EPOCH.fixed_offset(), Tz::UTC)
+      .map(|_| ())
+      .ok_or_else(|| format!("Invalid timestamp format {:?}", self.format))
+  }
+
+  fn text_edits(&self, source: RopeSlice, range: Range) -> Option<Vec<TextEdit>> {
+    let start = source.position(range.start);
+    self
+      .rewrites(&source.slice(source.range(range)).to_string())?
+      .into_iter()
+      .map(|(rewrite, new_text)| TextEdit {
+        range: source.lsp_range(start + rewrite.start..start + rewrite.end),
+        new_text,
+      })
+      .collect::<Vec<_>>()
+      .into()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use ropey::Rope;
+  use tower_lsp::lsp_types::Position;
+
+  fn normalize(format: &str, zone: &str) -> NormalizeTimestamps {
+    NormalizeTimestamps {
+      format: format.to_string(),
+      zone: zone.to_string(),
+      year: 2025,
+    }
+  }
+
+  const LOG: &str = concat!(
+    "2025-03-09T20:05:26.535680Z INFO start\n",
+    "Mar  9 20:05:26 host sshd[1]: ok\n",
+    "1.2.3.4 - - [09/Mar/2025:21:05:26 +0100] \"GET /\"\n",
+    "epoch=1741550726 took 1741550726.5 s\n",
+    "local 2025-03-09 15:05:26-05:00 id=12345678901\n",
+  );
+
+  #[test]
+  fn test_normalize_timestamps_mixed_formats() {
+    assert_eq!(
+      normalize("rfc3339", "UTC").transform(Rope::from_str(LOG).slice(..)),
+      Some(
+        concat!(
+          "2025-03-09T20:05:26.535680Z INFO start\n",
+          "2025-03-09T20:05:26Z host sshd[1]: ok\n",
+          "1.2.3.4 - - [2025-03-09T20:05:26Z] \"GET /\"\n",
+          "epoch=2025-03-09T20:05:26Z took 2025-03-09T20:05:26.500Z s\n",
+          "local 2025-03-09T20:05:26Z id=12345678901\n",
+        )
+        .to_string()
+      )
+    );
+    assert_eq!(
+      normalize("%d.%m.%Y %H:%M %Z", "Europe/Berlin")
+        .transform(Rope::from_str("at 1741550726").slice(..))
+        .as_deref(),
+      Some("at 09.03.2025 21:05 CET")
+    );
+  }
+
+  #[test]
+  fn test_normalize_timestamps_edits() {
+    let rope = Rope::from_str(LOG);
+    let source = rope.slice(..);
+    let edits = normalize("rfc3339", "UTC")
+      .text_edits(source, source.range_full())
+      .unwrap();
+    assert_eq!(edits.len(), 5);
+    assert_eq!(
+      edits[0].range,
+      Range::new(Position::new(1, 0), Position::new(1, 15))
+    );
+    assert_eq!(
+      normalize("rfc3339", "Mars/Olympus").validate(source, source.range_full()),
+      Err("Unknown time zone \"Mars/Olympus\"".to_string())
+    );
+    assert!(
+      normalize("%Q", "UTC")
+        .validate(source, source.range_full())
+        .is_err()
+    );
+  }
+}
@@ -0,0 +1,247 @@
+use crate::{
+  r#trait::{CommandMeta, Diff, Text, Transform},
+  r#type::{Config, Smarten, Straighten},
+};
+use ropey::RopeSlice;
+use tower_lsp::lsp_types::{CodeActionKind, Range, TextEdit};
+
+const STRAIGHTEN: [(char, &str); 20] = [
+  ('\u{2018}', "'"),
+  ('\u{2019}', "'"),
+  ('\u{201a}', "'"),
+  ('\u{201b}', "'"),
+  ('\u{2032}', "'"),
+  ('\u{201c}', "\""),
+  ('\u{201d}', "\""),
+  ('\u{201e}', "\""),
+  ('\u{201f}', "\""),
+  ('\u{2033}', "\""),
+  ('\u{2010}', "-"),
+  ('\u{2011}', "-"),
+  ('\u{2012}', "-"),
+  ('\u{2013}', "-"),
+  ('\u{2014}', "--"),
+  ('\u{2212}', "-"),
+  ('\u{2026}', "..."),
+  ('\u{00a0}', " "),
+  ('\u{2009}', " "),
+  ('\u{202f}', " "),
+];
+
+impl From<&Config> for Straighten {
+  fn from(config: &Config) -> Self {
+    Self {
+      mapping: STRAIGHTEN
+        .iter()
+        .map(|(c, ascii)| (*c, ascii.to_string()))
+        .chain(config.typography_mapping.clone())
+        .collect(),
+    }
+  }
+}
+
+impl CommandMeta for Straighten {
+  fn command_name(&self) -> &'static str {
+    "text-language-server.straighten"
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    "Straighten quotes, dashes and spaces"
+  }
+}
+
+impl Transform for Straighten {
+  fn code_action_kind(&self) -> Vec<CodeActionKind> {
+    vec![CodeActionKind::REFACTOR_REWRITE, CodeActionKind::SOURCE]
+  }
+
+  fn code_action_condition(&self, source: RopeSlice, range: Range) -> bool {
+    source
+      .slice(source.range(range))
+      .chars()
+      .any(|c| self.mapping.get(&c).is_some_and(|to| *to != c.to_string()))
+  }
+
+  fn transform(&self, text: RopeSlice) -> Option<String> {
+    text
+      .chars()
+      .fold(String::with_capacity(text.len()), |mut straight, c| {
+        match self.mapping.get(&c) {
+          Some(to) => straight.push_str(to),
+          None => straight.push(c),
+        }
+        straight
+      })
+      .into()
+  }
+
+  fn text_edits(&self, source: RopeSlice, range: Range) -> Option<Vec<TextEdit>> {
+    self
+      .transform(source.slice(source.range(range)))
+      .map(|new_text| source.diff(range, &new_text))
+  }
+}
+
+/// The configured straightening run backwards, longest ASCII first so that `---` wins over `--`.
+/// Entries that map a character to itself or to nothing can't be undone and are left out.
+impl From<&Config> for Smarten {
+  fn from(config: &Config) -> Self {
+    let mut mapping = config
+      .typography_mapping
+      .iter()
+      .filter(|(c, ascii)| !ascii.is_empty() && **ascii != c.to_string())
+      .map(|(c, ascii)| (ascii.clone(), *c))
+      .collect::<Vec<_>>();
+    mapping.sort_by_key(|(ascii, _)| std::cmp::Reverse(ascii.len()));
+    Self { mapping }
+  }
+}
+
+fn is_word(c: Option<&char>) -> bool {
+  c.is_some_and(|c| c.is_alphanumeric() || *c == '_')
+}
+
+/// A quote opens at the start of the text or after whitespace, an opening bracket or a dash,
+/// and closes everywhere else, which also turns apostrophes into `’`.
+fn opens(previous: Option<char>) -> bool {
+  previous.is_none_or(|c| c.is_whitespace() || "([{\u{2013}\u{2014}".contains(c))
+}
+
+impl CommandMeta for Smarten {
+  fn command_name(&self) -> &'static str {
+    "text-language-server.smarten"
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    "Smarten quotes, dashes and ellipses"
+  }
+}
+
+impl Transform for Smarten {
+  fn code_action_kind(&self) -> Vec<CodeActionKind> {
+    vec![CodeActionKind::REFACTOR_REWRITE, CodeActionKind::SOURCE]
+  }
+
+  fn code_action_condition(&self, source: RopeSlice, range: Range) -> bool {
+    let text = source.slice(source.range(range));
+    self.transform(text).is_some_and(|smart| smart != text)
+  }
+
+  /// `--` only becomes an em dash between word characters, so command-line flags and `--`
+  /// separators are left alone.
+  fn transform(&self, text: RopeSlice) -> Option<String> {
+    let text = self
+      .mapping
+      .iter()
+      .fold(text.to_string(), |text, (ascii, c)| {
+        text.replace(ascii.as_str(), &c.to_string())
+      })
+      .replace("...", "\u{2026}")
+      .replace(" - ", " \u{2013} ")
+      .chars()
+      .collect::<Vec<_>>();
+    let mut smart = String::with_capacity(text.len());
+    let mut previous = None;
+    let mut idx = 0;
+    while idx < text.len() {
+      let c = text[idx];
+      idx += 1;
+      match c {
+        '-'
+          if text.get(idx) == Some(&'-')
+            && is_word(text.get(idx.wrapping_sub(2)))
+            && is_word(text.get(idx + 1)) =>
+        {
+          smart.push('\u{2014}');
+          idx += 1;
+          previous = Some('\u{2014}');
+          continue;
+        }
+        '"' if opens(previous) => smart.push('\u{201c}'),
+        '"' => smart.push('\u{201d}'),
+        '\'' if opens(previous) => smart.push('\u{2018}'),
+        '\'' => smart.push('\u{2019}'),
+        c => smart.push(c),
+      }
+      previous = Some(c);
+    }
+    Some(smart)
+  }
+
+  fn text_edits(&self, source: RopeSlice, range: Range) -> Option<Vec<TextEdit>> {
+    self
+      .transform(source.slice(source.range(range)))
+      .map(|new_text| source.diff(range, &new_text))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use ropey::Rope;
+
+  const SMART: &str = "\u{201c}It\u{2019}s 5\u{00a0}km \u{2013} isn\u{2019}t it?\u{201d} she said\u{2014}then \u{2018}wait\u{2026}\u{2019}";
+
+  #[test]
+  fn test_straighten() {
+    let straighten = Straighten::from(&Config::default());
+    assert_eq!(
+      straighten
+        .transform(Rope::from_str(SMART).slice(..))
+        .as_deref(),
+      Some(r#""It's 5 km - isn't it?" she said--then 'wait...'"#)
+    );
+    let config = Config {
+      typography_mapping: [
+        ('\u{2014}', " - ".to_string()),
+        ('\u{00a0}', "\u{00a0}".to_string()),
+      ]
+      .into(),
+      ..Default::default()
+    };
+    assert_eq!(
+      Straighten::from(&config)
+        .transform(Rope::from_str(SMART).slice(..))
+        .as_deref(),
+      Some("\"It's 5\u{00a0}km - isn't it?\" she said - then 'wait...'")
+    );
+  }
+
+  fn smarten(smarten: &Smarten, text: &str) -> String {
+    smarten.transform(Rope::from_str(text).slice(..)).unwrap()
+  }
+
+  #[test]
+  fn test_smarten() {
+    let smart = Smarten::from(&Config::default());
+    assert_eq!(
+      smarten(
+        &smart,
+        r#""It's 5 km - isn't it?" she said--then 'wait...'"#
+      ),
+      SMART.replace('\u{00a0}', " ")
+    );
+    assert_eq!(
+      smarten(&smart, "run --verbose -- a, b --"),
+      "run --verbose -- a, b --"
+    );
+    let config = Config {
+      typography_mapping: [
+        ('\u{2014}', " - ".to_string()),
+        ('\u{00a0}', "\u{00a0}".to_string()),
+      ]
+      .into(),
+      ..Default::default()
+    };
+    assert_eq!(
+      smarten(&Smarten::from(&config), "5 km - done"),
+      "5 km\u{2014}done"
+    );
+    let rope = Rope::from_str("a \"b\"\nplain\n");
+    let source = rope.slice(..);
+    assert_eq!(
+      smart.text_edits(source, source.range_full()).unwrap().len(),
+      1
+    );
+  }
+}
@@ -0,0 +1,197 @@
+use crate::{
+  r#trait::{CommandMeta, Generate, Text, Transform},
+  r#type::{EpochToUTC, NewUuid, UuidFormat, UuidTimestamp},
+};
+use ::uuid::Uuid;
+use base64::{
+  Engine,
+  engine::general_purpose::{STANDARD_NO_PAD, URL_SAFE_NO_PAD},
+};
+use ropey::RopeSlice;
+use tower_lsp::lsp_types::{CodeActionKind, Range};
+
+impl CommandMeta for NewUuid {
+  fn command_name(&self) -> &'static str {
+    match self {
+      NewUuid::V4 => "text-language-server.new-uuid-v4",
+      NewUuid::V7 => "text-language-server.new-uuid-v7",
+    }
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    match self {
+      NewUuid::V4 => "New UUID v4",
+      NewUuid::V7 => "New UUID v7",
+    }
+  }
+}
+
+impl Generate for NewUuid {
+  fn generate(&self) -> Option<String> {
+    match self {
+      NewUuid::V4 => Uuid::new_v4(),
+      NewUuid::V7 => Uuid::now_v7(),
+    }
+    .hyphenated()
+    .to_string()
+    .into()
+  }
+}
+
+impl CommandMeta for UuidFormat {
+  fn command_name(&self) -> &'static str {
+    match self {
+      UuidFormat::Hyphenated => "text-language-server.uuid-hyphenated",
+      UuidFormat::Simple => "text-language-server.uuid-simple",
+      UuidFormat::Braced => "text-language-server.uuid-braced",
+      UuidFormat::Urn => "text-language-server.uuid-urn",
+      UuidFormat::Base64 => "text-language-server.uuid-base64",
+    }
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    match self {
+      UuidFormat::Hyphenated => "UUID to hyphenated",
+      UuidFormat::Simple => "UUID to simple",
+      UuidFormat::Braced => "UUID to braced",
+      UuidFormat::Urn => "UUID to URN",
+      UuidFormat::Base64 => "UUID to Base64",
+    }
+  }
+}
+
+impl Transform for UuidFormat {
+  fn code_action_kind(&self) -> Vec<CodeActionKind> {
+    vec![CodeActionKind::QUICKFIX, CodeActionKind::SOURCE]
+  }
+
+  fn code_action_condition(&self, source: RopeSlice, range: Range) -> bool {
+    let text = source.slice(source.range(range)).to_string();
+    parse(&text)
+      .map(|uuid| self.format(uuid))
+      .is_some_and(|formatted| formatted != text.trim())
+  }
+
+  fn transform(&self, text: RopeSlice) -> Option<String> {
+    parse_explicit(&text.to_string()).map(|uuid| self.format(uuid))
+  }
+}
+
+impl UuidFormat {
+  fn format(&self, uuid: Uuid) -> String {
+    match self {
+      UuidFormat::Hyphenated => uuid.hyphenated().to_string(),
+      UuidFormat::Simple => uuid.simple().to_string(),
+      UuidFormat::Braced => uuid.braced().to_string(),
+      UuidFormat::Urn => uuid.urn().to_string(),
+      UuidFormat::Base64 => URL_SAFE_NO_PAD.encode(uuid.as_bytes()),
+    }
+  }
+}
+
+impl CommandMeta for UuidTimestamp {
+  fn command_name(&self) -> &'static str {
+    "text-language-server.uuid-timestamp"
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    "UUID timestamp"
+  }
+}
+
+impl Transform for UuidTimestamp {
+  fn code_action_kind(&self) -> Vec<CodeActionKind> {
+    vec![CodeActionKind::QUICKFIX, CodeActionKind::SOURCE]
+  }
+
+  fn code_action_condition(&self, source: RopeSlice, range: Range) -> bool {
+    parse(&source.slice(source.range(range)).to_string())
+      .and_then(timestamp)
+      .is_some()
+  }
+
+  fn transform(&self, text: RopeSlice) -> Option<String> {
+    parse_explicit(&text.to_string()).and_then(timestamp)
+  }
+}
+
+fn timestamp(uuid: Uuid) -> Option<String> {
+  let (secs, nanos) = uuid.get_timestamp()?.to_unix();
+  EpochToUTC::format_with_nanos(secs.try_into().ok()?, nanos)
+}
+
+/// Parses the hyphenated, simple, braced and URN forms.
+fn parse(text: &str) -> Option<Uuid> {
+  Uuid::parse_str(text.trim()).ok()
+}
+
+/// Like [`parse`], falling back to 22 character Base64. Any identifier of that length decodes,
+/// so this is only for explicitly invoked commands, never for deciding what to offer.
+fn parse_explicit(text: &str) -> Option<Uuid> {
+  parse(text).or_else(|| {
+    let text = text.trim().trim_end_matches('=');
+    (text.len() == 22)
+      .then(|| {
+        URL_SAFE_NO_PAD
+          .decode(text)
+          .or_else(|_| STANDARD_NO_PAD.decode(text))
+          .ok()
+      })
+      .flatten()
+      .and_then(|bytes| Uuid::from_slice(&bytes).ok())
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use ropey::Rope;
+  use strum::IntoEnumIterator;
+
+  #[test]
+  fn test_uuid_format_round_trip() {
+    let uuid = "67e55044-10b1-426f-9247-bb680e5fe0c8";
+    let formatted = UuidFormat::iter()
+      .map(|format| format.transform(Rope::from_str(uuid).slice(..)).unwrap())
+      .collect::<Vec<_>>();
+    assert_eq!(
+      formatted,
+      [
+        "67e55044-10b1-426f-9247-bb680e5fe0c8",
+        "67e5504410b1426f9247bb680e5fe0c8",
+        "{67e55044-10b1-426f-9247-bb680e5fe0c8}",
+        "urn:uuid:67e55044-10b1-426f-9247-bb680e5fe0c8",
+        "Z-VQRBCxQm-SR7toDl_gyA",
+      ]
+    );
+    formatted
+      .iter()
+      .for_each(|text| assert_eq!(parse_explicit(text).unwrap().hyphenated().to_string(), uuid));
+  }
+
+  #[test]
+  fn test_uuid_timestamp() {
+    let timestamp = |uuid: &str| UuidTimestamp.transform(Rope::from_str(uuid).slice(..));
+    assert_eq!(
+      timestamp("017f22e2-79b0-7cc3-98c4-dc0c0c07398f").as_deref(),
+      Some("2022-02-22T19:22:22Z")
+    );
+    assert_eq!(
+      timestamp("c232ab00-9414-11ec-b3c8-9f6bdeced846").as_deref(),
+      Some("2022-02-22T19:22:22Z")
+    );
+    assert_eq!(timestamp("67e55044-10b1-426f-9247-bb680e5fe0c8"), None);
+  }
+
+  #[test]
+  fn test_uuid_base64_not_offered() {
+    let source = Rope::from_str("Z-VQRBCxQm-SR7toDl_gyA");
+    let source = source.slice(..);
+    assert!(!UuidFormat::Hyphenated.code_action_condition(source, source.range_full()));
+    assert!(!UuidTimestamp.code_action_condition(source, source.range_full()));
+    assert_eq!(
+      UuidFormat::Hyphenated.transform(source).as_deref(),
+      Some("67e55044-10b1-426f-9247-bb680e5fe0c8")
+    );
+  }
+}
@@ -0,0 +1,227 @@
+use crate::{
+  r#trait::{CommandMeta, Text, Transform},
+  r#type::{BumpVersion, SortVersions},
+};
+use ropey::RopeSlice;
+use semver::{BuildMetadata, Prerelease, Version};
+use tower_lsp::lsp_types::{CodeActionKind, Range};
+
+/// A version with an optional `v` prefix, as used in git tags and changelog headings.
+fn parse(text: &str) -> Option<(&str, Version)> {
+  let (prefix, version) = match text.strip_prefix(['v', 'V']) {
+    Some(version) => text.split_at(text.len() - version.len()),
+    None => ("", text),
+  };
+  Some((prefix, Version::parse(version).ok()?))
+}
+
+/// Bumps the rightmost numeric identifier, or starts a `.0` counter when there is none.
+fn bump_pre_release(pre: &Prerelease) -> Option<Prerelease> {
+  let mut identifiers = pre.split('.').map(String::from).collect::<Vec<_>>();
+  match identifiers
+    .iter_mut()
+    .rev()
+    .find(|identifier| identifier.bytes().all(|byte| byte.is_ascii_digit()))
+  {
+    Some(number) => *number = number.parse::<u64>().ok()?.checked_add(1)?.to_string(),
+    None => identifiers.push(String::from("0")),
+  }
+  Prerelease::new(&identifiers.join(".")).ok()
+}
+
+impl BumpVersion {
+  /// A pre-release of the target version is released rather than skipped past, so
+  /// `2.0.0-rc.1` bumps to `2.0.0` as a major. Build metadata never carries over.
+  fn bump(&self, mut version: Version) -> Option<Version> {
+    let released = !version.pre.is_empty();
+    match self {
+      BumpVersion::Major if released && version.minor == 0 && version.patch == 0 => {}
+      BumpVersion::Major => {
+        version.major = version.major.checked_add(1)?;
+        version.minor = 0;
+        version.patch = 0;
+      }
+      BumpVersion::Minor if released && version.patch == 0 => {}
+      BumpVersion::Minor => {
+        version.minor = version.minor.checked_add(1)?;
+        version.patch = 0;
+      }
+      BumpVersion::Patch if released => {}
+      BumpVersion::Patch => version.patch = version.patch.checked_add(1)?,
+      BumpVersion::PreRelease if released => {
+        version.pre = bump_pre_release(&version.pre)?;
+      }
+      BumpVersion::PreRelease => {
+        version.patch = version.patch.checked_add(1)?;
+        version.pre = Prerelease::new("0").ok()?;
+      }
+    }
+    if !matches!(self, BumpVersion::PreRelease) {
+      version.pre = Prerelease::EMPTY;
+    }
+    version.build = BuildMetadata::EMPTY;
+    Some(version)
+  }
+}
+
+impl CommandMeta for BumpVersion {
+  fn command_name(&self) -> &'static str {
+    match self {
+      BumpVersion::Major => "text-language-server.bump-major",
+      BumpVersion::Minor => "text-language-server.bump-minor",
+      BumpVersion::Patch => "text-language-server.bump-patch",
+      BumpVersion::PreRelease => "text-language-server.bump-pre-release",
+    }
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    match self {
+      BumpVersion::Major => "Bump major version",
+      BumpVersion::Minor => "Bump minor version",
+      BumpVersion::Patch => "Bump patch version",
+      BumpVersion::PreRelease => "Bump pre-release version",
+    }
+  }
+}
+
+impl Transform for BumpVersion {
+  fn code_action_kind(&self) -> Vec<CodeActionKind> {
+    vec![CodeActionKind::QUICKFIX, CodeActionKind::SOURCE]
+  }
+
+  fn code_action_condition(&self, source: RopeSlice, range: Range) -> bool {
+    self.transform(source.slice(source.range(range))).is_some()
+  }
+
+  /// Whitespace around the version in the selection is kept.
+  fn transform(&self, text: RopeSlice) -> Option<String> {
+    let text = text.to_string();
+    let trimmed = text.trim();
+    let (prefix, version) = parse(trimmed)?;
+    let start = text.len() - text.trim_start().len();
+    Some(format!(
+      "{}{prefix}{}{}",
+      &text[..start],
+      self.bump(version)?,
+      &text[start + trimmed.len()..]
+    ))
+  }
+}
+
+impl CommandMeta for SortVersions {
+  fn command_name(&self) -> &'static str {
+    "text-language-server.sort-versions"
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    "Sort versions"
+  }
+}
+
+impl Transform for SortVersions {
+  fn code_action_kind(&self) -> Vec<CodeActionKind> {
+    vec![CodeActionKind::QUICKFIX, CodeActionKind::SOURCE]
+  }
+
+  fn code_action_condition(&self, source: RopeSlice, range: Range) -> bool {
+    let text = source.slice(source.range(range));
+    text.to_string().trim().contains('\n')
+      && self.transform(text).is_some_and(|sorted| sorted != text)
+  }
+
+  /// Lines are kept as written and ordered by SemVer precedence, so `1.0.0-rc.1` sorts before
+  /// `1.0.0` and `1.10.0` after `1.9.0`. Blank lines are dropped.
+  fn transform(&self, text: RopeSlice) -> Option<String> {
+    let text = text.to_string();
+    let mut versions = text
+      .lines()
+      .filter(|line| !line.trim().is_empty())
+      .map(|line| parse(line.trim()).map(|(_, version)| (version, line)))
+      .collect::<Option<Vec<_>>>()?;
+    versions.sort_by(|(a, _), (b, _)| a.cmp(b));
+    let mut sorted = versions
+      .into_iter()
+      .map(|(_, line)| line)
+      .collect::<Vec<_>>()
+      .join("\n");
+    if text.ends_with('\n') {
+      sorted.push('\n');
+    }
+    Some(sorted)
+  }
+
+  fn validate(&self, source: RopeSlice, range: Range) -> Result<(), String> {
+    source
+      .slice(source.range(range))
+      .to_string()
+      .lines()
+      .enumerate()
+      .find(|(_, line)| !line.trim().is_empty() && parse(line.trim()).is_none())
+      .map_or(Ok(()), |(idx, line)| {
+        Err(format!(
+          "Invalid version on line {}: {:?}",
+          range.start.line as usize + idx + 1,
+          line.trim()
+        ))
+      })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use ropey::Rope;
+  use strum::IntoEnumIterator;
+
+  fn bump(text: &str) -> Vec<String> {
+    BumpVersion::iter()
+      .map(|bump| bump.transform(Rope::from_str(text).slice(..)).unwrap())
+      .collect()
+  }
+
+  #[test]
+  fn test_bump_version() {
+    assert_eq!(
+      bump("v1.2.3+build.7"),
+      vec!["v2.0.0", "v1.3.0", "v1.2.4", "v1.2.4-0"]
+    );
+    assert_eq!(
+      bump(" 2.0.0-rc.1 "),
+      vec![" 2.0.0 ", " 2.0.0 ", " 2.0.0 ", " 2.0.0-rc.2 "]
+    );
+    assert_eq!(
+      bump("1.4.2-alpha"),
+      vec!["2.0.0", "1.5.0", "1.4.2", "1.4.2-alpha.0"]
+    );
+    assert_eq!(
+      BumpVersion::Patch.transform(Rope::from_str("1.2").slice(..)),
+      None
+    );
+    let max = u64::MAX;
+    assert_eq!(
+      BumpVersion::iter()
+        .map(|bump| {
+          bump.transform(Rope::from_str(&format!("{max}.{max}.{max}-rc.{max}")).slice(..))
+        })
+        .collect::<Vec<_>>(),
+      vec![None, None, Some(format!("{max}.{max}.{max}")), None]
+    );
+  }
+
+  #[test]
+  fn test_sort_versions() {
+    let rope =
+      Rope::from_str("1.10.0\nv1.9.0\n1.0.0\n\n1.0.0-rc.1\n1.0.0-alpha.10\n1.0.0-alpha.2\n");
+    let source = rope.slice(..);
+    assert_eq!(
+      SortVersions.transform(source).as_deref(),
+      Some("1.0.0-alpha.2\n1.0.0-alpha.10\n1.0.0-rc.1\n1.0.0\nv1.9.0\n1.10.0\n")
+    );
+    let rope = Rope::from_str("1.0.0\nnext\n");
+    let source = rope.slice(..);
+    assert_eq!(
+      SortVersions.validate(source, source.range_full()),
+      Err("Invalid version on line 2: \"next\"".to_string())
+    );
+  }
+}
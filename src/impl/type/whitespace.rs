@@ -0,0 +1,578 @@
+use crate::{
+  r#trait::{CommandMeta, Diff, Text, Transform},
+  r#type::{
+    Config, Dedent, EnsureFinalNewline, Indent, IndentStyle, LineEnding, NormalizeLineEndings,
+    Reindent, SpacesToTabs, TabsToSpaces, TrimTrailingWhitespace,
+  },
+};
+use ropey::{LineType, RopeSlice};
+use std::{
+  collections::{BTreeMap, BTreeSet},
+  iter,
+};
+use tap::prelude::*;
+use tower_lsp::lsp_types::{CodeActionKind, Range, TextEdit};
+
+impl LineEnding {
+  fn as_str(&self) -> &'static str {
+    match self {
+      LineEnding::Lf => "\n",
+      LineEnding::Crlf => "\r\n",
+      LineEnding::Cr => "\r",
+    }
+  }
+}
+
+impl From<&Config> for NormalizeLineEndings {
+  fn from(config: &Config) -> Self {
+    Self {
+      line_ending: config.line_ending,
+    }
+  }
+}
+
+impl From<&Config> for SpacesToTabs {
+  fn from(config: &Config) -> Self {
+    Self {
+      tab_width: config.tab_width,
+    }
+  }
+}
+
+impl From<&Config> for TabsToSpaces {
+  fn from(config: &Config) -> Self {
+    Self {
+      tab_width: config.tab_width,
+    }
+  }
+}
+
+impl From<&Config> for Indent {
+  fn from(config: &Config) -> Self {
+    Self {
+      width: config.indent_width,
+    }
+  }
+}
+
+impl Reindent {
+  pub fn new(style: IndentStyle, config: &Config) -> Self {
+    Self {
+      style,
+      indent_width: config.indent_width,
+      tab_width: config.tab_width,
+    }
+  }
+}
+
+impl CommandMeta for TrimTrailingWhitespace {
+  fn command_name(&self) -> &'static str {
+    "text-language-server.trim-trailing-whitespace"
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    "Trim trailing whitespace"
+  }
+}
+
+impl Transform for TrimTrailingWhitespace {
+  fn code_action_kind(&self) -> Vec<CodeActionKind> {
+    vec![CodeActionKind::REFACTOR_REWRITE, CodeActionKind::SOURCE]
+  }
+
+  fn code_action_condition(&self, source: RopeSlice, range: Range) -> bool {
+    changes(self, source, range)
+  }
+
+  fn transform(&self, text: RopeSlice) -> Option<String> {
+    map_lines(text, |content| content.trim_end().to_string()).pipe(Some)
+  }
+
+  fn text_edits(&self, source: RopeSlice, range: Range) -> Option<Vec<TextEdit>> {
+    minimal_text_edits(self, source, range)
+  }
+}
+
+impl CommandMeta for TabsToSpaces {
+  fn command_name(&self) -> &'static str {
+    "text-language-server.tabs-to-spaces"
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    "Tabs to spaces"
+  }
+}
+
+impl Transform for TabsToSpaces {
+  fn code_action_kind(&self) -> Vec<CodeActionKind> {
+    vec![CodeActionKind::REFACTOR_REWRITE, CodeActionKind::SOURCE]
+  }
+
+  fn code_action_condition(&self, source: RopeSlice, range: Range) -> bool {
+    changes(self, source, range)
+  }
+
+  fn transform(&self, text: RopeSlice) -> Option<String> {
+    let tab_width = self.tab_width.max(1);
+    map_lines(text, |content| {
+      content.chars().fold(String::new(), |mut line, c| {
+        if c == '\t' {
+          let column = line.chars().count();
+          line.extend(iter::repeat_n(' ', tab_width - column % tab_width));
+        } else {
+          line.push(c);
+        }
+        line
+      })
+    })
+    .pipe(Some)
+  }
+
+  fn text_edits(&self, source: RopeSlice, range: Range) -> Option<Vec<TextEdit>> {
+    minimal_text_edits(self, source, range)
+  }
+}
+
+impl CommandMeta for SpacesToTabs {
+  fn command_name(&self) -> &'static str {
+    "text-language-server.spaces-to-tabs"
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    "Spaces to tabs"
+  }
+}
+
+impl Transform for SpacesToTabs {
+  fn code_action_kind(&self) -> Vec<CodeActionKind> {
+    vec![CodeActionKind::REFACTOR_REWRITE, CodeActionKind::SOURCE]
+  }
+
+  fn code_action_condition(&self, source: RopeSlice, range: Range) -> bool {
+    changes(self, source, range)
+  }
+
+  /// Only leading indentation is converted, so alignment inside lines is left alone.
+  fn transform(&self, text: RopeSlice) -> Option<String> {
+    let tab_width = self.tab_width.max(1);
+    map_lines(text, |content| {
+      let body = content.trim_start_matches([' ', '\t']);
+      let column = width(leading(content), tab_width);
+      iter::repeat_n('\t', column / tab_width)
+        .chain(iter::repeat_n(' ', column % tab_width))
+        .chain(body.chars())
+        .collect()
+    })
+    .pipe(Some)
+  }
+
+  fn text_edits(&self, source: RopeSlice, range: Range) -> Option<Vec<TextEdit>> {
+    minimal_text_edits(self, source, range)
+  }
+}
+
+impl CommandMeta for NormalizeLineEndings {
+  fn command_name(&self) -> &'static str {
+    "text-language-server.normalize-line-endings"
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    "Normalize line endings"
+  }
+}
+
+impl Transform for NormalizeLineEndings {
+  fn code_action_kind(&self) -> Vec<CodeActionKind> {
+    vec![CodeActionKind::REFACTOR_REWRITE, CodeActionKind::SOURCE]
+  }
+
+  fn code_action_condition(&self, source: RopeSlice, range: Range) -> bool {
+    changes(self, source, range)
+  }
+
+  fn transform(&self, text: RopeSlice) -> Option<String> {
+    let mut output = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+      match c {
+        '\r' => {
+          chars.next_if_eq(&'\n');
+          output.push_str(self.line_ending.as_str());
+        }
+        '\n' => output.push_str(self.line_ending.as_str()),
+        c => output.push(c),
+      }
+    }
+    Some(output)
+  }
+
+  fn text_edits(&self, source: RopeSlice, range: Range) -> Option<Vec<TextEdit>> {
+    minimal_text_edits(self, source, range)
+  }
+}
+
+impl CommandMeta for EnsureFinalNewline {
+  fn command_name(&self) -> &'static str {
+    "text-language-server.ensure-final-newline"
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    "Ensure final newline"
+  }
+}
+
+impl Transform for EnsureFinalNewline {
+  fn code_action_kind(&self) -> Vec<CodeActionKind> {
+    vec![CodeActionKind::REFACTOR_REWRITE, CodeActionKind::SOURCE]
+  }
+
+  /// Only a selection that reaches the end of the document has a final newline to fix.
+  fn code_action_condition(&self, source: RopeSlice, range: Range) -> bool {
+    range.end == source.range_full().end && changes(self, source, range)
+  }
+
+  fn transform(&self, text: RopeSlice) -> Option<String> {
+    let text = text.to_string();
+    let body = text.trim_end_matches(['\r', '\n']);
+    if body.is_empty() {
+      return Some(String::new());
+    }
+    let line_ending = if text.contains("\r\n") {
+      "\r\n"
+    } else if text.contains('\n') || !text.contains('\r') {
+      "\n"
+    } else {
+      "\r"
+    };
+    Some(format!("{body}{line_ending}"))
+  }
+
+  fn text_edits(&self, source: RopeSlice, range: Range) -> Option<Vec<TextEdit>> {
+    minimal_text_edits(self, source, range)
+  }
+}
+
+impl CommandMeta for Reindent {
+  fn command_name(&self) -> &'static str {
+    match self.style {
+      IndentStyle::Spaces => "text-language-server.reindent-spaces",
+      IndentStyle::Tabs => "text-language-server.reindent-tabs",
+    }
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    match self.style {
+      IndentStyle::Spaces => "Re-indent with spaces",
+      IndentStyle::Tabs => "Re-indent with tabs",
+    }
+  }
+}
+
+impl Transform for Reindent {
+  fn code_action_kind(&self) -> Vec<CodeActionKind> {
+    vec![CodeActionKind::REFACTOR_REWRITE, CodeActionKind::SOURCE]
+  }
+
+  fn code_action_condition(&self, source: RopeSlice, range: Range) -> bool {
+    changes(self, source, range)
+  }
+
+  /// One level of the selection is a tab when it is indented with tabs alone, and otherwise the
+  /// most common step between the indents of consecutive lines, so aligned continuation lines
+  /// don't skew it. With fewer than two distinct indents there is no step to go by, so a level
+  /// is `tab_width` columns. Columns left over below a whole level are kept as spaces.
+  fn transform(&self, text: RopeSlice) -> Option<String> {
+    let tab_width = self.tab_width.max(1);
+    let string = text.to_string();
+    let indents = string
+      .lines()
+      .filter(|line| !line.trim().is_empty())
+      .map(|line| width(leading(line), tab_width))
+      .collect::<Vec<_>>();
+    let level = if tab_indented(&string) || indents.iter().collect::<BTreeSet<_>>().len() < 2 {
+      tab_width
+    } else {
+      let mut steps = BTreeMap::<usize, usize>::new();
+      let mut previous = 0;
+      for column in indents {
+        if column != previous {
+          *steps.entry(column.abs_diff(previous)).or_default() += 1;
+        }
+        previous = column;
+      }
+      // Ties go to the smaller step.
+      steps
+        .into_iter()
+        .max_by(|(a, a_count), (b, b_count)| a_count.cmp(b_count).then(b.cmp(a)))
+        .map_or(0, |(step, _)| step)
+    };
+    if level == 0 {
+      return Some(text.to_string());
+    }
+    map_lines(text, |content| {
+      if content.trim().is_empty() {
+        return content.to_string();
+      }
+      let indent = leading(content);
+      let column = width(indent, tab_width);
+      let (levels, rest) = (column / level, column % level);
+      match self.style {
+        IndentStyle::Spaces => iter::repeat_n(' ', levels * self.indent_width + rest)
+          .chain(content[indent.len()..].chars())
+          .collect(),
+        IndentStyle::Tabs => iter::repeat_n('\t', levels)
+          .chain(iter::repeat_n(' ', rest))
+          .chain(content[indent.len()..].chars())
+          .collect(),
+      }
+    })
+    .pipe(Some)
+  }
+
+  fn text_edits(&self, source: RopeSlice, range: Range) -> Option<Vec<TextEdit>> {
+    minimal_text_edits(self, source, range)
+  }
+}
+
+impl CommandMeta for Dedent {
+  fn command_name(&self) -> &'static str {
+    "text-language-server.dedent"
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    "Dedent"
+  }
+}
+
+impl Transform for Dedent {
+  fn code_action_kind(&self) -> Vec<CodeActionKind> {
+    vec![CodeActionKind::REFACTOR_REWRITE, CodeActionKind::SOURCE]
+  }
+
+  fn code_action_condition(&self, source: RopeSlice, range: Range) -> bool {
+    changes(self, source, range)
+  }
+
+  /// Blank lines don't count towards the common prefix and lose whatever part of it they have.
+  fn transform(&self, text: RopeSlice) -> Option<String> {
+    let string = text.to_string();
+    let prefix = string
+      .lines()
+      .filter(|line| !line.trim().is_empty())
+      .map(leading)
+      .reduce(|prefix, indent| {
+        let common = prefix
+          .bytes()
+          .zip(indent.bytes())
+          .take_while(|(a, b)| a == b)
+          .count();
+        &prefix[..common]
+      })
+      .unwrap_or_default();
+    map_lines(text, |content| {
+      content
+        .strip_prefix(prefix)
+        .unwrap_or(content.trim_start())
+        .to_string()
+    })
+    .pipe(Some)
+  }
+
+  fn text_edits(&self, source: RopeSlice, range: Range) -> Option<Vec<TextEdit>> {
+    minimal_text_edits(self, source, range)
+  }
+}
+
+impl CommandMeta for Indent {
+  fn command_name(&self) -> &'static str {
+    "text-language-server.indent"
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    "Indent"
+  }
+}
+
+impl Transform for Indent {
+  fn code_action_kind(&self) -> Vec<CodeActionKind> {
+    vec![CodeActionKind::REFACTOR_REWRITE, CodeActionKind::SOURCE]
+  }
+
+  fn code_action_condition(&self, source: RopeSlice, range: Range) -> bool {
+    changes(self, source, range)
+  }
+
+  /// A selection indented with tabs alone gets another tab rather than `width` spaces.
+  fn transform(&self, text: RopeSlice) -> Option<String> {
+    let unit = if tab_indented(&text.to_string()) {
+      String::from("\t")
+    } else {
+      " ".repeat(self.width)
+    };
+    map_lines(text, |content| {
+      if content.trim().is_empty() {
+        content.to_string()
+      } else {
+        unit.clone() + content
+      }
+    })
+    .pipe(Some)
+  }
+
+  fn text_edits(&self, source: RopeSlice, range: Range) -> Option<Vec<TextEdit>> {
+    minimal_text_edits(self, source, range)
+  }
+}
+
+/// The leading spaces and tabs of a line.
+fn leading(content: &str) -> &str {
+  &content[..content.len() - content.trim_start_matches([' ', '\t']).len()]
+}
+
+/// Whether the indented lines of `text` are indented with tabs alone.
+fn tab_indented(text: &str) -> bool {
+  let indents = text
+    .lines()
+    .filter(|line| !line.trim().is_empty())
+    .map(leading)
+    .collect::<Vec<_>>();
+  indents.iter().any(|indent| indent.contains('\t'))
+    && indents.iter().all(|indent| !indent.contains(' '))
+}
+
+/// The column an indent reaches, with tabs advancing to the next tab stop.
+fn width(indent: &str, tab_width: usize) -> usize {
+  indent.chars().fold(0, |column, c| match c {
+    '\t' => column + tab_width - column % tab_width,
+    _ => column + 1,
+  })
+}
+
+/// Applies `f` to each line without its line ending, keeping the line endings as they were.
+fn map_lines(text: RopeSlice, f: impl Fn(&str) -> String) -> String {
+  text
+    .lines(LineType::LF_CR)
+    .map(|line| {
+      let line = line.to_string();
+      let content = line.trim_end_matches(['\r', '\n']);
+      f(content) + &line[content.len()..]
+    })
+    .collect()
+}
+
+fn changes(transform: &impl Transform, source: RopeSlice, range: Range) -> bool {
+  let text = source.slice(source.range(range));
+  transform
+    .transform(text)
+    .is_some_and(|new_text| text != new_text.as_str())
+}
+
+fn minimal_text_edits(
+  transform: &impl Transform,
+  source: RopeSlice,
+  range: Range,
+) -> Option<Vec<TextEdit>> {
+  transform
+    .transform(source.slice(source.range(range)))
+    .map(|new_text| source.diff(range, &new_text))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use ropey::Rope;
+  use tower_lsp::lsp_types::Position;
+
+  fn apply(transform: &impl Transform, text: &str) -> String {
+    transform.transform(Rope::from_str(text).slice(..)).unwrap()
+  }
+
+  #[test]
+  fn test_trim_trailing_whitespace() {
+    assert_eq!(
+      apply(&TrimTrailingWhitespace, "a \t\r\nb\n  \nc  "),
+      "a\r\nb\n\nc"
+    );
+  }
+
+  #[test]
+  fn test_tabs_and_spaces() {
+    assert_eq!(
+      apply(&TabsToSpaces { tab_width: 4 }, "\tab\tc\n  \td"),
+      "    ab  c\n    d"
+    );
+    assert_eq!(
+      apply(&SpacesToTabs { tab_width: 4 }, "      a  b\n  \tc\nd"),
+      "\t  a  b\n\tc\nd"
+    );
+  }
+
+  #[test]
+  fn test_line_endings() {
+    assert_eq!(
+      apply(
+        &NormalizeLineEndings {
+          line_ending: LineEnding::Crlf
+        },
+        "a\rb\nc\r\nd"
+      ),
+      "a\r\nb\r\nc\r\nd"
+    );
+    assert_eq!(apply(&EnsureFinalNewline, "a\nb"), "a\nb\n");
+    assert_eq!(apply(&EnsureFinalNewline, "a\r\nb\r\n\r\n"), "a\r\nb\r\n");
+    assert_eq!(apply(&EnsureFinalNewline, "\n\n"), "");
+    let rope = Rope::from_str("a\nb\nc");
+    let source = rope.slice(..);
+    assert!(
+      !EnsureFinalNewline
+        .code_action_condition(source, Range::new(Position::new(0, 0), Position::new(1, 1)))
+    );
+    assert!(
+      EnsureFinalNewline
+        .code_action_condition(source, Range::new(Position::new(1, 0), Position::new(2, 1)))
+    );
+    let rope = Rope::from_str("a\rb\r");
+    let source = rope.slice(..);
+    let edits = NormalizeLineEndings {
+      line_ending: LineEnding::Lf,
+    }
+    .text_edits(source, source.range_full())
+    .unwrap();
+    assert_eq!(edits.len(), 2);
+  }
+
+  #[test]
+  fn test_reindent() {
+    let reindent = |style, indent_width| Reindent {
+      style,
+      indent_width,
+      tab_width: 4,
+    };
+    let code = "if a:\n    if b:\n\n        c(1,\n          2)\n";
+    assert_eq!(
+      apply(&reindent(IndentStyle::Spaces, 2), code),
+      "if a:\n  if b:\n\n    c(1,\n      2)\n"
+    );
+    assert_eq!(
+      apply(&reindent(IndentStyle::Tabs, 2), "  a\n    b\n  c"),
+      "\ta\n\t\tb\n\tc"
+    );
+    assert_eq!(
+      apply(&reindent(IndentStyle::Spaces, 4), "\ta\n\t\tb"),
+      "    a\n        b"
+    );
+    assert_eq!(
+      apply(&reindent(IndentStyle::Spaces, 2), "        a\n"),
+      "    a\n"
+    );
+  }
+
+  #[test]
+  fn test_dedent_and_indent() {
+    assert_eq!(apply(&Dedent, "  a\n \n    b\r\n"), "a\n\n  b\r\n");
+    assert_eq!(apply(&Dedent, "\ta\n    b"), "\ta\n    b");
+    assert_eq!(apply(&Dedent, "    a\n\n      b\n"), "a\n\n  b\n");
+    assert_eq!(apply(&Indent { width: 2 }, "a\n\n  b"), "  a\n\n    b");
+    assert_eq!(apply(&Indent { width: 2 }, "a\n\tb"), "\ta\n\t\tb");
+  }
+}
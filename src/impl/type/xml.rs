@@ -0,0 +1,409 @@
+use crate::{
+  r#trait::{CommandMeta, Text, Transform},
+  r#type::{Config, XmlMinify, XmlPretty},
+};
+use ropey::RopeSlice;
+use tap::prelude::*;
+use tower_lsp::lsp_types::{CodeActionKind, Range};
+use xmlparser::{ElementEnd, Token, Tokenizer};
+
+enum Node<'a> {
+  Element(Element<'a>),
+  Text(&'a str),
+  /// Comments, processing instructions, declarations, doctypes and CDATA, kept byte for byte.
+  Markup(&'a str),
+}
+
+struct Element<'a> {
+  name: &'a str,
+  open: &'a str,
+  attributes: Vec<&'a str>,
+  children: Vec<Node<'a>>,
+  /// `None` for a self-closing tag.
+  close: Option<&'a str>,
+  /// The element exactly as written, used where whitespace is significant.
+  raw: &'a str,
+  start: usize,
+  preserve: bool,
+}
+
+impl Element<'_> {
+  fn open_tag(&self) -> String {
+    self
+      .attributes
+      .iter()
+      .fold(self.open.to_string(), |tag, attribute| {
+        format!("{tag} {attribute}")
+      })
+  }
+
+  /// Text next to child elements makes every whitespace run potentially significant.
+  fn mixed(&self) -> bool {
+    self.children.iter().any(|child| match child {
+      Node::Text(text) => !text.trim().is_empty(),
+      Node::Markup(markup) => markup.starts_with("<![CDATA["),
+      Node::Element(_) => false,
+    })
+  }
+
+  fn verbatim(&self) -> bool {
+    self.preserve || self.mixed()
+  }
+}
+
+/// Parse error with the byte offset it occurred at.
+struct ParseError {
+  offset: usize,
+  message: String,
+}
+
+fn parse(text: &str) -> Result<Vec<Node<'_>>, ParseError> {
+  let mut roots = Vec::new();
+  let mut stack = Vec::<Element>::new();
+  let mut dtd_start = None;
+  for token in Tokenizer::from(text) {
+    let token = token.map_err(|err| ParseError {
+      offset: offset(text, err.pos().row, err.pos().col),
+      message: err
+        .to_string()
+        .replacen(&format!(" at {}", err.pos()), "", 1),
+    })?;
+    match token {
+      Token::Declaration { span, .. }
+      | Token::ProcessingInstruction { span, .. }
+      | Token::Comment { span, .. }
+      | Token::Cdata { span, .. }
+      | Token::EmptyDtd { span, .. } => push(&mut stack, &mut roots, Node::Markup(span.as_str())),
+      Token::DtdStart { span, .. } => dtd_start = Some(span.start()),
+      Token::EntityDeclaration { .. } => {}
+      Token::DtdEnd { span } => {
+        let start = dtd_start.take().unwrap_or(span.start());
+        push(
+          &mut stack,
+          &mut roots,
+          Node::Markup(&text[start..span.end()]),
+        );
+      }
+      Token::ElementStart { span, .. } => stack.push(Element {
+        name: &span.as_str()[1..],
+        open: span.as_str(),
+        attributes: Vec::new(),
+        children: Vec::new(),
+        close: None,
+        raw: "",
+        start: span.start(),
+        preserve: stack.last().is_some_and(|parent| parent.preserve),
+      }),
+      Token::Attribute {
+        prefix,
+        local,
+        value,
+        span,
+      } => {
+        if let Some(element) = stack.last_mut() {
+          if prefix.as_str() == "xml" && local.as_str() == "space" {
+            element.preserve = value.as_str() == "preserve";
+          }
+          element.attributes.push(span.as_str());
+        }
+      }
+      Token::ElementEnd {
+        end: ElementEnd::Open,
+        ..
+      } => {}
+      Token::ElementEnd { end, span } => {
+        let Some(mut element) = stack.pop() else {
+          return Err(ParseError {
+            offset: span.start(),
+            message: format!("unexpected closing tag {}", span.as_str()),
+          });
+        };
+        if let ElementEnd::Close(prefix, local) = end {
+          let name = if prefix.is_empty() {
+            local.to_string()
+          } else {
+            format!("{prefix}:{local}")
+          };
+          if name != element.name {
+            return Err(ParseError {
+              offset: span.start(),
+              message: format!("closing tag </{name}> does not match <{}>", element.name),
+            });
+          }
+          element.close = Some(span.as_str());
+        }
+        element.raw = &text[element.start..span.end()];
+        push(&mut stack, &mut roots, Node::Element(element));
+      }
+      Token::Text { text } => push(&mut stack, &mut roots, Node::Text(text.as_str())),
+    }
+  }
+  match stack.pop() {
+    Some(element) => Err(ParseError {
+      offset: element.start,
+      message: format!("unclosed element <{}>", element.name),
+    }),
+    None => Ok(roots),
+  }
+}
+
+fn push<'a>(stack: &mut [Element<'a>], roots: &mut Vec<Node<'a>>, node: Node<'a>) {
+  match stack.last_mut() {
+    Some(parent) => parent.children.push(node),
+    None => roots.push(node),
+  }
+}
+
+/// Byte offset of a 1-based row and character column.
+fn offset(text: &str, row: u32, col: u32) -> usize {
+  let line_start = text
+    .match_indices('\n')
+    .nth((row as usize).saturating_sub(2))
+    .filter(|_| row > 1)
+    .map(|(idx, _)| idx + 1)
+    .unwrap_or(0);
+  text[line_start..]
+    .char_indices()
+    .nth((col as usize).saturating_sub(1))
+    .map(|(idx, _)| line_start + idx)
+    .unwrap_or(text.len())
+}
+
+fn minify(nodes: &[Node], out: &mut String) {
+  for node in nodes {
+    match node {
+      Node::Element(element) if element.verbatim() => out.push_str(element.raw),
+      Node::Element(element) => {
+        out.push_str(&element.open_tag());
+        match element.close {
+          Some(close) => {
+            out.push('>');
+            minify(&element.children, out);
+            out.push_str(close);
+          }
+          None => out.push_str("/>"),
+        }
+      }
+      Node::Text(text) if text.trim().is_empty() => {}
+      Node::Text(text) | Node::Markup(text) => out.push_str(text),
+    }
+  }
+}
+
+fn pretty(nodes: &[Node], depth: usize, indent_width: usize, out: &mut String) {
+  let indent = " ".repeat(depth * indent_width);
+  for node in nodes {
+    match node {
+      Node::Element(element) if element.verbatim() => {
+        out.push_str(&indent);
+        out.push_str(element.raw);
+      }
+      Node::Element(element) => {
+        out.push_str(&indent);
+        out.push_str(&element.open_tag());
+        match element.close {
+          None => out.push_str("/>"),
+          Some(close)
+            if element
+              .children
+              .iter()
+              .all(|child| matches!(child, Node::Text(_))) =>
+          {
+            out.push('>');
+            out.push_str(close);
+          }
+          Some(close) => {
+            out.push_str(">\n");
+            pretty(&element.children, depth + 1, indent_width, out);
+            out.push_str(&indent);
+            out.push_str(close);
+          }
+        }
+      }
+      Node::Text(text) if text.trim().is_empty() => continue,
+      Node::Text(text) => {
+        out.push_str(&indent);
+        out.push_str(text.trim());
+      }
+      Node::Markup(markup) => {
+        out.push_str(&indent);
+        out.push_str(markup);
+      }
+    }
+    out.push('\n');
+  }
+}
+
+fn looks_like_xml(text: &str) -> bool {
+  let text = text.trim();
+  text.starts_with('<') && text.ends_with('>')
+}
+
+/// Reports malformed input at its line and column in the document, both 1-based.
+fn validate(source: RopeSlice, range: Range) -> Result<(), String> {
+  let start = source.position(range.start);
+  parse(&source.slice(source.range(range)).to_string())
+    .map(|_| ())
+    .map_err(|err| {
+      let position = source.lsp_position(start + err.offset);
+      format!(
+        "Malformed XML at {}:{}: {}",
+        position.line + 1,
+        position.character + 1,
+        err.message
+      )
+    })
+}
+
+impl From<&Config> for XmlPretty {
+  fn from(config: &Config) -> Self {
+    Self {
+      indent_width: config.indent_width,
+    }
+  }
+}
+
+impl CommandMeta for XmlPretty {
+  fn command_name(&self) -> &'static str {
+    "text-language-server.xml-pretty"
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    "Pretty-print XML"
+  }
+}
+
+impl Transform for XmlPretty {
+  fn code_action_kind(&self) -> Vec<CodeActionKind> {
+    vec![CodeActionKind::QUICKFIX, CodeActionKind::SOURCE]
+  }
+
+  fn code_action_condition(&self, source: RopeSlice, range: Range) -> bool {
+    let text = source.slice(source.range(range));
+    looks_like_xml(&text.to_string())
+      && self
+        .transform(text)
+        .is_none_or(|xml| xml != text.to_string().trim_start())
+  }
+
+  /// Whitespace after the last node of the selection is kept, so a trailing newline survives.
+  fn transform(&self, text: RopeSlice) -> Option<String> {
+    let text = text.to_string();
+    let nodes = parse(&text).ok()?;
+    let xml = String::new().tap_mut(|out| pretty(&nodes, 0, self.indent_width, out));
+    Some(format!(
+      "{}{}",
+      xml.trim_end(),
+      &text[text.trim_end().len()..]
+    ))
+  }
+
+  fn validate(&self, source: RopeSlice, range: Range) -> Result<(), String> {
+    validate(source, range)
+  }
+}
+
+impl CommandMeta for XmlMinify {
+  fn command_name(&self) -> &'static str {
+    "text-language-server.xml-minify"
+  }
+
+  fn command_display_name(&self) -> &'static str {
+    "Minify XML"
+  }
+}
+
+impl Transform for XmlMinify {
+  fn code_action_kind(&self) -> Vec<CodeActionKind> {
+    vec![CodeActionKind::QUICKFIX, CodeActionKind::SOURCE]
+  }
+
+  fn code_action_condition(&self, source: RopeSlice, range: Range) -> bool {
+    let text = source.slice(source.range(range));
+    looks_like_xml(&text.to_string())
+      && self
+        .transform(text)
+        .is_none_or(|xml| xml != text.to_string().trim())
+  }
+
+  fn transform(&self, text: RopeSlice) -> Option<String> {
+    let text = text.to_string();
+    let nodes = parse(&text).ok()?;
+    String::new().tap_mut(|out| minify(&nodes, out)).pipe(Some)
+  }
+
+  fn validate(&self, source: RopeSlice, range: Range) -> Result<(), String> {
+    validate(source, range)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use ropey::Rope;
+
+  const XML: &str = concat!(
+    r#"<?xml version="1.0"?><!-- pom --><project a="1"  b='2'><name>demo</name>"#,
+    r#"<script><![CDATA[ if (a < b) {} ]]></script><empty/><pre xml:space="preserve">  x"#,
+    r#"  <b> y </b></pre><?pi data?><p>Hello <b>world</b></p></project>"#,
+  );
+
+  #[test]
+  fn test_xml_pretty_and_minify_round_trip() {
+    let pretty = XmlPretty { indent_width: 2 }
+      .transform(Rope::from_str(XML).slice(..))
+      .unwrap();
+    assert_eq!(
+      pretty,
+      [
+        r#"<?xml version="1.0"?>"#,
+        "<!-- pom -->",
+        r#"<project a="1" b='2'>"#,
+        "  <name>demo</name>",
+        "  <script><![CDATA[ if (a < b) {} ]]></script>",
+        "  <empty/>",
+        r#"  <pre xml:space="preserve">  x  <b> y </b></pre>"#,
+        "  <?pi data?>",
+        "  <p>Hello <b>world</b></p>",
+        "</project>",
+      ]
+      .join("\n")
+    );
+    assert_eq!(
+      XmlMinify.transform(Rope::from_str(&pretty).slice(..)),
+      Some(XML.replace("  b=", " b="))
+    );
+    assert_eq!(
+      XmlPretty { indent_width: 2 }
+        .transform(Rope::from_str("<a><b/></a>\n").slice(..))
+        .as_deref(),
+      Some("<a>\n  <b/>\n</a>\n")
+    );
+  }
+
+  #[test]
+  fn test_xml_malformed_position() {
+    let rope = Rope::from_str("text\n<a>\n  <b></c>\n</a>");
+    let source = rope.slice(..);
+    let range = Range::new(
+      tower_lsp::lsp_types::Position::new(1, 0),
+      source.range_full().end,
+    );
+    assert_eq!(
+      XmlPretty { indent_width: 2 }.validate(source, range),
+      Err("Malformed XML at 3:6: closing tag </c> does not match <b>".to_string())
+    );
+    assert!(XmlMinify.validate(source, range).is_err());
+    let rope = Rope::from_str("<a><b></a>");
+    assert_eq!(
+      XmlMinify.validate(rope.slice(..), rope.slice(..).range_full()),
+      Err("Malformed XML at 1:7: closing tag </a> does not match <b>".to_string())
+    );
+    let rope = Rope::from_str("<a b=1/>");
+    assert!(
+      XmlMinify
+        .validate(rope.slice(..), rope.slice(..).range_full())
+        .is_err_and(|err| err.starts_with("Malformed XML at 1:"))
+    );
+  }
+}
@@ -1,23 +1,35 @@
 use crate::{
   r#trait::{CodeAction, CommandMeta, ExecuteCommand, Text, WithServer},
-  r#type::{EpochToUTC, Source, Unescape},
+  r#type::{
+    Append, BumpVersion, Calculate, CidrInfo, ColorFormat, Compress, Config, DecodeJwt, Decompress,
+    Dedent, Digest, DigestAlgorithm, DomainFormat, DurationFormat, EnsureFinalNewline, EpochToUTC,
+    ExplainCron, Fold, HexToText, Hexdump, HumanizeBytes, Indent, IndentStyle, Insert, IpFormat,
+    LoremIpsum, NewUuid, NormalizeLineEndings, NormalizeTimestamps, ParseBytes, ParseHexdump,
+    RandomToken, Reflow, RegexReplace, Reindent, ShellJoin, ShellQuote, ShellSplit, ShellUnquote,
+    Slugify, Smarten, SortVersions, Source, SpacesToTabs, SqlFormat, Straighten, StringLiteral,
+    SummarizeNetworks, TabsToSpaces, TextToHex, TokenEncoding, TrimTrailingWhitespace, Unescape,
+    Unwrap, UuidFormat, UuidTimestamp, XmlMinify, XmlPretty,
+  },
 };
 use bon::Builder;
 use getset::Getters;
-use ropey::Rope;
+use ropey::{LineType, Rope};
 use scc::HashMap;
-use serde_json::Value;
+use serde_json::{Value, from_value};
 use std::{ops::Deref, process};
+use strum::IntoEnumIterator;
 use tap::prelude::*;
+use tokio::sync::RwLock;
 use tower_lsp::{
   Client, LanguageServer,
   jsonrpc::Result,
   lsp_types::{
     CodeActionKind, CodeActionOptions, CodeActionParams, CodeActionResponse,
-    DidChangeTextDocumentParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams,
-    ExecuteCommandOptions, ExecuteCommandParams, InitializeParams, InitializeResult,
-    InitializedParams, MessageType, ServerCapabilities, TextDocumentSyncCapability,
-    TextDocumentSyncKind, Url,
+    DidChangeConfigurationParams, DidChangeTextDocumentParams, DidCloseTextDocumentParams,
+    DidOpenTextDocumentParams, ExecuteCommandOptions, ExecuteCommandParams, Hover, HoverContents,
+    HoverParams, HoverProviderCapability, InitializeParams, InitializeResult, InitializedParams,
+    MarkupContent, MarkupKind, MessageType, Position, ServerCapabilities,
+    TextDocumentSyncCapability, TextDocumentSyncKind, Url,
   },
 };
 
@@ -27,27 +39,117 @@ pub struct Server {
   client: Client,
   #[builder(default)]
   text: HashMap<Url, Rope>,
+  #[builder(default)]
+  config: RwLock<Config>,
+}
+
+impl Server {
+  async fn configure(&self, settings: Value) {
+    match from_value::<Config>(settings) {
+      Ok(config) => *self.config.write().await = config,
+      Err(err) => {
+        self
+          .client
+          .log_message(
+            MessageType::WARNING,
+            format!("Invalid configuration: {err}"),
+          )
+          .await
+      }
+    }
+  }
 }
 
 #[tower_lsp::async_trait]
 impl LanguageServer for Server {
   #[tracing::instrument(ret)]
-  async fn initialize(&self, _: InitializeParams) -> Result<InitializeResult> {
+  async fn initialize(&self, params: InitializeParams) -> Result<InitializeResult> {
+    if let Some(settings) = params.initialization_options {
+      self.configure(settings).await;
+    }
+    let config = self.config.read().await.clone();
     Ok(InitializeResult {
       server_info: None,
       capabilities: ServerCapabilities {
         text_document_sync: Some(TextDocumentSyncCapability::Kind(
           TextDocumentSyncKind::INCREMENTAL,
         )),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
         code_action_provider: Some(Into::into(CodeActionOptions {
-          code_action_kinds: Some(vec![CodeActionKind::SOURCE]),
+          code_action_kinds: Some(vec![
+            CodeActionKind::SOURCE,
+            CodeActionKind::REFACTOR_REWRITE,
+          ]),
           ..Default::default()
         })),
         execute_command_provider: Some(ExecuteCommandOptions {
           commands: [
             Unescape.command_display_name(),
             EpochToUTC.command_display_name(),
+            Reflow::from(&config).command_display_name(),
+            Unwrap.command_display_name(),
+            TrimTrailingWhitespace.command_display_name(),
+            TabsToSpaces::from(&config).command_display_name(),
+            SpacesToTabs::from(&config).command_display_name(),
+            NormalizeLineEndings::from(&config).command_display_name(),
+            EnsureFinalNewline.command_display_name(),
+            DecodeJwt.command_display_name(),
+            UuidTimestamp.command_display_name(),
+            Calculate.command_display_name(),
+            Append(Calculate).command_display_name(),
+            ParseBytes.command_display_name(),
+            ShellQuote.command_display_name(),
+            ShellUnquote.command_display_name(),
+            ShellSplit.command_display_name(),
+            ShellJoin.command_display_name(),
+            RegexReplace::default().command_display_name(),
+            XmlPretty::from(&config).command_display_name(),
+            XmlMinify.command_display_name(),
+            SqlFormat::from(&config).command_display_name(),
+            Decompress::from(&config).command_display_name(),
+            HexToText.command_display_name(),
+            TextToHex.command_display_name(),
+            Hexdump.command_display_name(),
+            ParseHexdump.command_display_name(),
+            Slugify::from(&config).command_display_name(),
+            Straighten::from(&config).command_display_name(),
+            Smarten::from(&config).command_display_name(),
+            config.password.command_display_name(),
+            config.random_int.command_display_name(),
+            LoremIpsum.command_display_name(),
+            NormalizeTimestamps::from(&config).command_display_name(),
+            ExplainCron::from(&config).command_display_name(),
+            CidrInfo.command_display_name(),
+            SummarizeNetworks.command_display_name(),
+            SortVersions.command_display_name(),
+            Dedent.command_display_name(),
+            Indent::from(&config).command_display_name(),
           ]
+          .into_iter()
+          .chain(DigestAlgorithm::iter().flat_map(|algorithm| {
+            [
+              Digest::new(algorithm, &config).command_display_name(),
+              Append(Digest::new(algorithm, &config)).command_display_name(),
+            ]
+          }))
+          .chain(NewUuid::iter().map(|new_uuid| new_uuid.command_display_name()))
+          .chain(UuidFormat::iter().map(|format| format.command_display_name()))
+          .chain(HumanizeBytes::iter().map(|humanize| humanize.command_display_name()))
+          .chain(DurationFormat::iter().map(|format| format.command_display_name()))
+          .chain(ColorFormat::iter().map(|format| format.command_display_name()))
+          .chain(Compress::iter().map(|compress| compress.command_display_name()))
+          .chain(DomainFormat::iter().map(|format| format.command_display_name()))
+          .chain(Fold::iter().map(|fold| fold.command_display_name()))
+          .chain(IpFormat::iter().map(|format| format.command_display_name()))
+          .chain(BumpVersion::iter().map(|bump| bump.command_display_name()))
+          .chain(StringLiteral::iter().map(|literal| literal.command_display_name()))
+          .chain(
+            IndentStyle::iter().map(|style| Reindent::new(style, &config).command_display_name()),
+          )
+          .chain(
+            TokenEncoding::iter()
+              .map(|encoding| RandomToken::new(encoding, &config).command_display_name()),
+          )
           .map(ToString::to_string)
           .pipe(Vec::from_iter),
           ..Default::default()
@@ -77,6 +179,11 @@ impl LanguageServer for Server {
     Ok(())
   }
 
+  #[tracing::instrument(ret)]
+  async fn did_change_configuration(&self, params: DidChangeConfigurationParams) {
+    self.configure(params.settings).await;
+  }
+
   #[tracing::instrument(ret)]
   async fn did_open(&self, params: DidOpenTextDocumentParams) {
     self
@@ -110,27 +217,480 @@ impl LanguageServer for Server {
     self.text.remove_async(&params.text_document.uri).await;
   }
 
+  #[tracing::instrument(ret, err)]
+  async fn hover(&self, params: HoverParams) -> Result<Option<Hover>> {
+    let config = self.config.read().await.clone();
+    let position = params.text_document_position_params.position;
+    let Some(rope) = self
+      .text
+      .get_async(&params.text_document_position_params.text_document.uri)
+      .await
+      .map(|rope| rope.clone())
+    else {
+      return Ok(None);
+    };
+    let source = rope.slice(..);
+    let Some(line) = source.lines(LineType::LF_CR).nth(position.line as usize) else {
+      return Ok(None);
+    };
+    let offset = source.position(position) - source.position(Position::new(position.line, 0));
+    ExplainCron::from(&config)
+      .hover(&line.to_string(), offset)
+      .map(|explanation| Hover {
+        contents: HoverContents::Markup(MarkupContent {
+          kind: MarkupKind::PlainText,
+          value: explanation,
+        }),
+        range: None,
+      })
+      .pipe(Ok)
+  }
+
   #[rustfmt::skip]
   #[tracing::instrument(ret, err)]
   async fn code_action(&self, params: CodeActionParams) -> Result<Option<CodeActionResponse>> {
-    Vec::new()
+    let config = self.config.read().await.clone();
+    let mut actions = Vec::new()
       .into_iter()
       .chain(Unescape.with_server(self).code_action(&params).await?)
       .chain(Source(Unescape).with_server(self).code_action(&params).await?)
       .chain(EpochToUTC.with_server(self).code_action(&params).await?)
-      .pipe(Vec::from_iter)
-      .pipe(Some)
-      .pipe(Ok)
+      .chain(Reflow::from(&config).with_server(self).code_action(&params).await?)
+      .chain(Unwrap.with_server(self).code_action(&params).await?)
+      .chain(TrimTrailingWhitespace.with_server(self).code_action(&params).await?)
+      .chain(Source(TrimTrailingWhitespace).with_server(self).code_action(&params).await?)
+      .chain(TabsToSpaces::from(&config).with_server(self).code_action(&params).await?)
+      .chain(Source(TabsToSpaces::from(&config)).with_server(self).code_action(&params).await?)
+      .chain(SpacesToTabs::from(&config).with_server(self).code_action(&params).await?)
+      .chain(Source(SpacesToTabs::from(&config)).with_server(self).code_action(&params).await?)
+      .chain(NormalizeLineEndings::from(&config).with_server(self).code_action(&params).await?)
+      .chain(Source(NormalizeLineEndings::from(&config)).with_server(self).code_action(&params).await?)
+      .chain(EnsureFinalNewline.with_server(self).code_action(&params).await?)
+      .chain(Source(EnsureFinalNewline).with_server(self).code_action(&params).await?)
+      .chain(DecodeJwt.with_server(self).code_action(&params).await?)
+      .chain(UuidTimestamp.with_server(self).code_action(&params).await?)
+      .chain(Calculate.with_server(self).code_action(&params).await?)
+      .chain(Append(Calculate).with_server(self).code_action(&params).await?)
+      .chain(ParseBytes.with_server(self).code_action(&params).await?)
+      .chain(ShellQuote.with_server(self).code_action(&params).await?)
+      .chain(ShellUnquote.with_server(self).code_action(&params).await?)
+      .chain(ShellSplit.with_server(self).code_action(&params).await?)
+      .chain(ShellJoin.with_server(self).code_action(&params).await?)
+      .chain(XmlPretty::from(&config).with_server(self).code_action(&params).await?)
+      .chain(Source(XmlPretty::from(&config)).with_server(self).code_action(&params).await?)
+      .chain(XmlMinify.with_server(self).code_action(&params).await?)
+      .chain(Source(XmlMinify).with_server(self).code_action(&params).await?)
+      .chain(SqlFormat::from(&config).with_server(self).code_action(&params).await?)
+      .chain(Decompress::from(&config).with_server(self).code_action(&params).await?)
+      .chain(HexToText.with_server(self).code_action(&params).await?)
+      .chain(TextToHex.with_server(self).code_action(&params).await?)
+      .chain(Hexdump.with_server(self).code_action(&params).await?)
+      .chain(ParseHexdump.with_server(self).code_action(&params).await?)
+      .chain(Slugify::from(&config).with_server(self).code_action(&params).await?)
+      .chain(Straighten::from(&config).with_server(self).code_action(&params).await?)
+      .chain(Source(Straighten::from(&config)).with_server(self).code_action(&params).await?)
+      .chain(Smarten::from(&config).with_server(self).code_action(&params).await?)
+      .chain(NormalizeTimestamps::from(&config).with_server(self).code_action(&params).await?)
+      .chain(Source(NormalizeTimestamps::from(&config)).with_server(self).code_action(&params).await?)
+      .chain(ExplainCron::from(&config).with_server(self).code_action(&params).await?)
+      .chain(CidrInfo.with_server(self).code_action(&params).await?)
+      .chain(SummarizeNetworks.with_server(self).code_action(&params).await?)
+      .chain(Source(SummarizeNetworks).with_server(self).code_action(&params).await?)
+      .chain(SortVersions.with_server(self).code_action(&params).await?)
+      .chain(Dedent.with_server(self).code_action(&params).await?)
+      .chain(Indent::from(&config).with_server(self).code_action(&params).await?)
+      .pipe(Vec::from_iter);
+    for algorithm in DigestAlgorithm::iter() {
+      actions.extend(Digest::new(algorithm, &config).with_server(self).code_action(&params).await?);
+      actions.extend(Append(Digest::new(algorithm, &config)).with_server(self).code_action(&params).await?);
+      actions.extend(Source(Append(Digest::new(algorithm, &config))).with_server(self).code_action(&params).await?);
+    }
+    for new_uuid in NewUuid::iter() {
+      actions.extend(Insert(new_uuid).with_server(self).code_action(&params).await?);
+    }
+    actions.extend(Insert(config.password.clone()).with_server(self).code_action(&params).await?);
+    for encoding in TokenEncoding::iter() {
+      actions.extend(Insert(RandomToken::new(encoding, &config)).with_server(self).code_action(&params).await?);
+    }
+    actions.extend(Insert(config.random_int.clone()).with_server(self).code_action(&params).await?);
+    actions.extend(Insert(LoremIpsum).with_server(self).code_action(&params).await?);
+    for format in UuidFormat::iter() {
+      actions.extend(format.with_server(self).code_action(&params).await?);
+    }
+    for humanize in HumanizeBytes::iter() {
+      actions.extend(humanize.with_server(self).code_action(&params).await?);
+    }
+    for format in DurationFormat::iter() {
+      actions.extend(format.with_server(self).code_action(&params).await?);
+    }
+    for format in ColorFormat::iter() {
+      actions.extend(format.with_server(self).code_action(&params).await?);
+    }
+    for compress in Compress::iter() {
+      actions.extend(compress.with_server(self).code_action(&params).await?);
+    }
+    for format in DomainFormat::iter() {
+      actions.extend(format.with_server(self).code_action(&params).await?);
+    }
+    for fold in Fold::iter() {
+      actions.extend(fold.with_server(self).code_action(&params).await?);
+    }
+    for format in IpFormat::iter() {
+      actions.extend(format.with_server(self).code_action(&params).await?);
+    }
+    for bump in BumpVersion::iter() {
+      actions.extend(bump.with_server(self).code_action(&params).await?);
+    }
+    for literal in StringLiteral::iter() {
+      actions.extend(literal.with_server(self).code_action(&params).await?);
+    }
+    for style in IndentStyle::iter() {
+      actions.extend(Reindent::new(style, &config).with_server(self).code_action(&params).await?);
+    }
+    Ok(Some(actions))
   }
 
   #[tracing::instrument(ret, err)]
   async fn execute_command(&self, params: ExecuteCommandParams) -> Result<Option<Value>> {
+    let config = self.config.read().await.clone();
     if Unescape.command_name() == params.command.as_str() {
       Unescape.with_server(self).execute_command(&params).await
     } else if EpochToUTC.command_name() == params.command.as_str() {
       EpochToUTC.with_server(self).execute_command(&params).await
+    } else if Reflow::from(&config).command_name() == params.command.as_str() {
+      Reflow::from(&config)
+        .with_server(self)
+        .execute_command(&params)
+        .await
+    } else if Unwrap.command_name() == params.command.as_str() {
+      Unwrap.with_server(self).execute_command(&params).await
+    } else if TrimTrailingWhitespace.command_name() == params.command.as_str() {
+      TrimTrailingWhitespace
+        .with_server(self)
+        .execute_command(&params)
+        .await
+    } else if TabsToSpaces::from(&config).command_name() == params.command.as_str() {
+      TabsToSpaces::from(&config)
+        .with_server(self)
+        .execute_command(&params)
+        .await
+    } else if SpacesToTabs::from(&config).command_name() == params.command.as_str() {
+      SpacesToTabs::from(&config)
+        .with_server(self)
+        .execute_command(&params)
+        .await
+    } else if NormalizeLineEndings::from(&config).command_name() == params.command.as_str() {
+      NormalizeLineEndings::from(&config)
+        .with_server(self)
+        .execute_command(&params)
+        .await
+    } else if EnsureFinalNewline.command_name() == params.command.as_str() {
+      EnsureFinalNewline
+        .with_server(self)
+        .execute_command(&params)
+        .await
+    } else if DecodeJwt.command_name() == params.command.as_str() {
+      DecodeJwt.with_server(self).execute_command(&params).await
+    } else if let Some(digest) = DigestAlgorithm::iter()
+      .map(|algorithm| Digest::new(algorithm, &config))
+      .find(|digest| digest.command_name() == params.command.as_str())
+    {
+      digest.with_server(self).execute_command(&params).await
+    } else if let Some(append) = DigestAlgorithm::iter()
+      .map(|algorithm| Append(Digest::new(algorithm, &config)))
+      .find(|append| append.command_name() == params.command.as_str())
+    {
+      append.with_server(self).execute_command(&params).await
+    } else if UuidTimestamp.command_name() == params.command.as_str() {
+      UuidTimestamp
+        .with_server(self)
+        .execute_command(&params)
+        .await
+    } else if let Some(new_uuid) =
+      NewUuid::iter().find(|new_uuid| new_uuid.command_name() == params.command.as_str())
+    {
+      Insert(new_uuid)
+        .with_server(self)
+        .execute_command(&params)
+        .await
+    } else if let Some(format) =
+      UuidFormat::iter().find(|format| format.command_name() == params.command.as_str())
+    {
+      format.with_server(self).execute_command(&params).await
+    } else if Calculate.command_name() == params.command.as_str() {
+      Calculate.with_server(self).execute_command(&params).await
+    } else if Append(Calculate).command_name() == params.command.as_str() {
+      Append(Calculate)
+        .with_server(self)
+        .execute_command(&params)
+        .await
+    } else if ParseBytes.command_name() == params.command.as_str() {
+      ParseBytes.with_server(self).execute_command(&params).await
+    } else if ShellQuote.command_name() == params.command.as_str() {
+      ShellQuote.with_server(self).execute_command(&params).await
+    } else if ShellUnquote.command_name() == params.command.as_str() {
+      ShellUnquote
+        .with_server(self)
+        .execute_command(&params)
+        .await
+    } else if ShellSplit.command_name() == params.command.as_str() {
+      ShellSplit.with_server(self).execute_command(&params).await
+    } else if ShellJoin.command_name() == params.command.as_str() {
+      ShellJoin.with_server(self).execute_command(&params).await
+    } else if RegexReplace::default().command_name() == params.command.as_str() {
+      RegexReplace::try_from(&params)?
+        .with_server(self)
+        .execute_command(&params)
+        .await
+    } else if XmlPretty::from(&config).command_name() == params.command.as_str() {
+      XmlPretty::from(&config)
+        .with_server(self)
+        .execute_command(&params)
+        .await
+    } else if XmlMinify.command_name() == params.command.as_str() {
+      XmlMinify.with_server(self).execute_command(&params).await
+    } else if SqlFormat::from(&config).command_name() == params.command.as_str() {
+      SqlFormat::from(&config)
+        .with_server(self)
+        .execute_command(&params)
+        .await
+    } else if let Some(humanize) =
+      HumanizeBytes::iter().find(|humanize| humanize.command_name() == params.command.as_str())
+    {
+      humanize.with_server(self).execute_command(&params).await
+    } else if let Some(format) =
+      DurationFormat::iter().find(|format| format.command_name() == params.command.as_str())
+    {
+      format.with_server(self).execute_command(&params).await
+    } else if let Some(format) =
+      ColorFormat::iter().find(|format| format.command_name() == params.command.as_str())
+    {
+      format.with_server(self).execute_command(&params).await
+    } else if Decompress::from(&config).command_name() == params.command.as_str() {
+      Decompress::from(&config)
+        .with_server(self)
+        .execute_command(&params)
+        .await
+    } else if HexToText.command_name() == params.command.as_str() {
+      HexToText.with_server(self).execute_command(&params).await
+    } else if TextToHex.command_name() == params.command.as_str() {
+      TextToHex.with_server(self).execute_command(&params).await
+    } else if Hexdump.command_name() == params.command.as_str() {
+      Hexdump.with_server(self).execute_command(&params).await
+    } else if ParseHexdump.command_name() == params.command.as_str() {
+      ParseHexdump
+        .with_server(self)
+        .execute_command(&params)
+        .await
+    } else if let Some(compress) =
+      Compress::iter().find(|compress| compress.command_name() == params.command.as_str())
+    {
+      compress.with_server(self).execute_command(&params).await
+    } else if let Some(format) =
+      DomainFormat::iter().find(|format| format.command_name() == params.command.as_str())
+    {
+      format.with_server(self).execute_command(&params).await
+    } else if let Some(fold) =
+      Fold::iter().find(|fold| fold.command_name() == params.command.as_str())
+    {
+      fold.with_server(self).execute_command(&params).await
+    } else if Slugify::from(&config).command_name() == params.command.as_str() {
+      Slugify::from(&config)
+        .with_server(self)
+        .execute_command(&params)
+        .await
+    } else if Straighten::from(&config).command_name() == params.command.as_str() {
+      Straighten::from(&config)
+        .with_server(self)
+        .execute_command(&params)
+        .await
+    } else if Smarten::from(&config).command_name() == params.command.as_str() {
+      Smarten::from(&config)
+        .with_server(self)
+        .execute_command(&params)
+        .await
+    } else if config.password.command_name() == params.command.as_str() {
+      Insert(config.password)
+        .with_server(self)
+        .execute_command(&params)
+        .await
+    } else if let Some(token) = TokenEncoding::iter()
+      .map(|encoding| RandomToken::new(encoding, &config))
+      .find(|token| token.command_name() == params.command.as_str())
+    {
+      Insert(token)
+        .with_server(self)
+        .execute_command(&params)
+        .await
+    } else if config.random_int.command_name() == params.command.as_str() {
+      Insert(config.random_int)
+        .with_server(self)
+        .execute_command(&params)
+        .await
+    } else if LoremIpsum.command_name() == params.command.as_str() {
+      Insert(LoremIpsum)
+        .with_server(self)
+        .execute_command(&params)
+        .await
+    } else if NormalizeTimestamps::from(&config).command_name() == params.command.as_str() {
+      NormalizeTimestamps::from(&config)
+        .with_server(self)
+        .execute_command(&params)
+        .await
+    } else if ExplainCron::from(&config).command_name() == params.command.as_str() {
+      ExplainCron::from(&config)
+        .with_server(self)
+        .execute_command(&params)
+        .await
+    } else if CidrInfo.command_name() == params.command.as_str() {
+      CidrInfo.with_server(self).execute_command(&params).await
+    } else if SummarizeNetworks.command_name() == params.command.as_str() {
+      SummarizeNetworks
+        .with_server(self)
+        .execute_command(&params)
+        .await
+    } else if let Some(format) =
+      IpFormat::iter().find(|format| format.command_name() == params.command.as_str())
+    {
+      format.with_server(self).execute_command(&params).await
+    } else if SortVersions.command_name() == params.command.as_str() {
+      SortVersions
+        .with_server(self)
+        .execute_command(&params)
+        .await
+    } else if let Some(bump) =
+      BumpVersion::iter().find(|bump| bump.command_name() == params.command.as_str())
+    {
+      bump.with_server(self).execute_command(&params).await
+    } else if let Some(literal) =
+      StringLiteral::iter().find(|literal| literal.command_name() == params.command.as_str())
+    {
+      literal.with_server(self).execute_command(&params).await
+    } else if Dedent.command_name() == params.command.as_str() {
+      Dedent.with_server(self).execute_command(&params).await
+    } else if Indent::from(&config).command_name() == params.command.as_str() {
+      Indent::from(&config)
+        .with_server(self)
+        .execute_command(&params)
+        .await
+    } else if let Some(reindent) = IndentStyle::iter()
+      .map(|style| Reindent::new(style, &config))
+      .find(|reindent| reindent.command_name() == params.command.as_str())
+    {
+      reindent.with_server(self).execute_command(&params).await
     } else {
       Ok(None)
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::json;
+  use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader, DuplexStream};
+  use tower_lsp::LspService;
+
+  struct Client {
+    reader: BufReader<tokio::io::ReadHalf<DuplexStream>>,
+    writer: tokio::io::WriteHalf<DuplexStream>,
+  }
+
+  impl Client {
+    fn start() -> Self {
+      let (client, server) = tokio::io::duplex(1 << 16);
+      let (server_reader, server_writer) = tokio::io::split(server);
+      let (service, socket) = LspService::new(|client| Server::builder().client(client).build());
+      tokio::spawn(tower_lsp::Server::new(server_reader, server_writer, socket).serve(service));
+      let (reader, writer) = tokio::io::split(client);
+      Self {
+        reader: BufReader::new(reader),
+        writer,
+      }
+    }
+
+    async fn send(&mut self, message: Value) {
+      let body = message.to_string();
+      let frame = format!("Content-Length: {}\r\n\r\n{body}", body.len());
+      self.writer.write_all(frame.as_bytes()).await.unwrap();
+    }
+
+    async fn receive(&mut self) -> Value {
+      let mut length = 0;
+      loop {
+        let mut header = String::new();
+        self.reader.read_line(&mut header).await.unwrap();
+        match header.trim_end().split_once(": ") {
+          Some(("Content-Length", value)) => length = value.parse().unwrap(),
+          None => break,
+          _ => {}
+        }
+      }
+      let mut body = vec![0; length];
+      self.reader.read_exact(&mut body).await.unwrap();
+      serde_json::from_slice(&body).unwrap()
+    }
+
+    /// The next message that isn't a log notification.
+    async fn receive_skipping_logs(&mut self) -> Value {
+      loop {
+        let message = self.receive().await;
+        if message["method"] != "window/logMessage" {
+          return message;
+        }
+      }
+    }
+  }
+
+  #[tokio::test]
+  async fn test_execute_calculate() {
+    let mut client = Client::start();
+    client
+      .send(
+        json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {"capabilities": {}}}),
+      )
+      .await;
+    client.receive_skipping_logs().await;
+    client
+      .send(json!({"jsonrpc": "2.0", "method": "initialized", "params": {}}))
+      .await;
+    let uri = "file:///calc.txt";
+    client
+      .send(json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didOpen",
+        "params": {"textDocument": {"uri": uri, "languageId": "plaintext", "version": 1, "text": "x = 6 * 7\n"}},
+      }))
+      .await;
+    let range = json!({"start": {"line": 0, "character": 4}, "end": {"line": 0, "character": 9}});
+    let end = json!({"start": {"line": 0, "character": 9}, "end": {"line": 0, "character": 9}});
+    for (id, command, edit) in [
+      (
+        2,
+        "text-language-server.calculate",
+        json!({"range": range, "newText": "42"}),
+      ),
+      (
+        3,
+        "text-language-server.append-calculate",
+        json!({"range": end, "newText": " = 42"}),
+      ),
+    ] {
+      client
+        .send(json!({
+          "jsonrpc": "2.0",
+          "id": id,
+          "method": "workspace/executeCommand",
+          "params": {"command": command, "arguments": [uri, range]},
+        }))
+        .await;
+      let request = client.receive_skipping_logs().await;
+      assert_eq!(request["method"], "workspace/applyEdit");
+      assert_eq!(request["params"]["edit"]["changes"][uri], json!([edit]));
+      client
+        .send(json!({"jsonrpc": "2.0", "id": request["id"], "result": {"applied": true}}))
+        .await;
+      assert_eq!(client.receive_skipping_logs().await["id"], id);
+    }
+  }
+}
@@ -4,7 +4,9 @@ use serde_json::Value;
 use std::ops;
 use tower_lsp::{
   jsonrpc::Result,
-  lsp_types::{self, CodeActionKind, CodeActionOrCommand, CodeActionParams, ExecuteCommandParams},
+  lsp_types::{
+    self, CodeActionKind, CodeActionOrCommand, CodeActionParams, ExecuteCommandParams, TextEdit,
+  },
 };
 
 pub trait CommandMeta {
@@ -16,22 +18,55 @@ pub trait CodeAction {
   async fn code_action(&self, params: &CodeActionParams) -> Result<Vec<CodeActionOrCommand>>;
 }
 
+pub trait Diff {
+  fn diff(&self, range: lsp_types::Range, new_text: &str) -> Vec<TextEdit>;
+}
+
 pub trait ExecuteCommand {
   async fn execute_command(&self, params: &ExecuteCommandParams) -> Result<Option<Value>>;
 }
 
+pub trait Generate {
+  fn generate(&self) -> Option<String>;
+}
+
 pub trait Text {
   fn position(&self, position: lsp_types::Position) -> usize;
   fn range_full(&self) -> lsp_types::Range;
   fn range(&self, range: lsp_types::Range) -> ops::Range<usize> {
     self.position(range.start)..self.position(range.end)
   }
+  fn lsp_position(&self, byte_idx: usize) -> lsp_types::Position;
+  fn lsp_range(&self, byte_range: ops::Range<usize>) -> lsp_types::Range {
+    lsp_types::Range {
+      start: self.lsp_position(byte_range.start),
+      end: self.lsp_position(byte_range.end),
+    }
+  }
 }
 
 pub trait Transform {
+  /// `QUICKFIX` is for transforms that only apply to a value written in a notation they
+  /// recognize, such as a timestamp, a UUID or an IP address. Transforms that apply to arbitrary
+  /// text, such as encoders, whitespace and prose edits, are `REFACTOR_REWRITE`, and both
+  /// directions of a feature share a kind. Either way the action is also offered as `SOURCE`.
   fn code_action_kind(&self) -> Vec<CodeActionKind>;
   fn code_action_condition(&self, source: RopeSlice, range: lsp_types::Range) -> bool;
   fn transform(&self, text: RopeSlice) -> Option<String>;
+  fn code_action_range(&self, _: RopeSlice, range: lsp_types::Range) -> lsp_types::Range {
+    range
+  }
+  fn validate(&self, _: RopeSlice, _: lsp_types::Range) -> std::result::Result<(), String> {
+    Ok(())
+  }
+  fn dry_run(&self, _: RopeSlice, _: lsp_types::Range) -> Option<Value> {
+    None
+  }
+  fn text_edits(&self, source: RopeSlice, range: lsp_types::Range) -> Option<Vec<TextEdit>> {
+    self
+      .transform(source.slice(source.range(range)))
+      .map(|new_text| vec![TextEdit { range, new_text }])
+  }
 }
 
 pub trait WithServer<'a, S>: Sized {
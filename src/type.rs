@@ -1,6 +1,9 @@
 use crate::server::Server;
 use bon::Builder;
+use chrono::{DateTime, Utc};
 use getset::Getters;
+use serde::Deserialize;
+use std::collections::BTreeMap;
 
 #[derive(derive_more::Deref, Builder, Getters)]
 pub struct WithServer<'a, T> {
@@ -10,7 +13,262 @@ pub struct WithServer<'a, T> {
   inner: T,
 }
 
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Config {
+  pub cron_runs: usize,
+  pub cron_zone: String,
+  pub decompress_limit: usize,
+  pub digest_encoding: DigestEncoding,
+  pub digest_input: DigestInput,
+  pub indent_width: usize,
+  pub line_ending: LineEnding,
+  pub password: Password,
+  pub random_int: RandomInt,
+  pub random_token_bytes: usize,
+  pub reflow_width: usize,
+  pub slug_max_length: Option<usize>,
+  pub slug_separator: String,
+  pub sql_keyword_case: SqlKeywordCase,
+  pub tab_width: usize,
+  pub timestamp_format: String,
+  pub timestamp_zone: String,
+  /// Replacements for `Straighten`, merged over its defaults.
+  pub typography_mapping: BTreeMap<char, String>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DigestEncoding {
+  Hex,
+  Base64,
+}
+
+/// Bytes a digest is computed over: UTF-8 with line endings normalized to LF, or the document's
+/// bytes with its line endings as they are.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum DigestInput {
+  Utf8Lf,
+  Raw,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEnding {
+  Lf,
+  Crlf,
+  Cr,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SqlKeywordCase {
+  Upper,
+  Lower,
+  Preserve,
+}
+
 pub struct Source<T>(pub T);
+pub struct Append<T>(pub T);
+pub struct Insert<T>(pub T);
 
+#[derive(Clone, Copy, strum::EnumIter)]
+pub enum BumpVersion {
+  Major,
+  Minor,
+  Patch,
+  PreRelease,
+}
+pub struct Calculate;
+pub struct CidrInfo;
+#[derive(Clone, Copy, strum::EnumIter)]
+pub enum ColorFormat {
+  Hex,
+  ShortHex,
+  Rgb,
+  Hsl,
+  Oklch,
+}
+#[derive(Clone, Copy, strum::EnumIter)]
+pub enum Compress {
+  Gzip,
+  Zlib,
+  Deflate,
+}
+pub struct DecodeJwt;
+pub struct Decompress {
+  pub limit: usize,
+}
+pub struct Dedent;
+pub struct Digest {
+  pub algorithm: DigestAlgorithm,
+  pub encoding: DigestEncoding,
+  pub input: DigestInput,
+}
+#[derive(Clone, Copy, strum::EnumIter)]
+pub enum DigestAlgorithm {
+  Sha256,
+  Sha1,
+  Md5,
+  Crc32,
+  Blake3,
+}
+#[derive(Clone, Copy, strum::EnumIter)]
+pub enum DomainFormat {
+  Ascii,
+  Unicode,
+}
+#[derive(Clone, Copy, strum::EnumIter)]
+pub enum DurationFormat {
+  Seconds,
+  Iso8601,
+  Go,
+  Compact,
+}
+pub struct EnsureFinalNewline;
 pub struct EpochToUTC;
+pub struct ExplainCron {
+  pub zone: String,
+  pub runs: usize,
+  pub now: DateTime<Utc>,
+}
+/// Folding to plain Latin. It deserializes from `strip-diacritics` or `transliterate` so that
+/// case-conversion transforms can take it as a setting; none exist yet.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, strum::EnumIter)]
+#[serde(rename_all = "kebab-case")]
+pub enum Fold {
+  StripDiacritics,
+  Transliterate,
+}
+pub struct HexToText;
+pub struct Hexdump;
+#[derive(Clone, Copy, strum::EnumIter)]
+pub enum HumanizeBytes {
+  Iec,
+  Si,
+}
+pub struct Indent {
+  pub width: usize,
+}
+#[derive(Clone, Copy, strum::EnumIter)]
+pub enum IndentStyle {
+  Spaces,
+  Tabs,
+}
+#[derive(Clone, Copy, strum::EnumIter)]
+pub enum IpFormat {
+  Integer,
+  DottedQuad,
+  Compressed,
+  Expanded,
+}
+pub struct LoremIpsum;
+#[derive(Clone, Copy, strum::EnumIter)]
+pub enum NewUuid {
+  V4,
+  V7,
+}
+pub struct NormalizeLineEndings {
+  pub line_ending: LineEnding,
+}
+/// `year` fills in syslog timestamps, which don't carry one.
+pub struct NormalizeTimestamps {
+  pub format: String,
+  pub zone: String,
+  pub year: i32,
+}
+pub struct ParseBytes;
+pub struct ParseHexdump;
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Password {
+  pub length: usize,
+  pub lowercase: bool,
+  pub uppercase: bool,
+  pub digits: bool,
+  pub symbols: bool,
+}
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct RandomInt {
+  pub min: i64,
+  pub max: i64,
+}
+pub struct RandomToken {
+  pub encoding: TokenEncoding,
+  pub bytes: usize,
+}
+pub struct Reflow {
+  pub width: usize,
+}
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct RegexReplace {
+  pub pattern: String,
+  pub replacement: String,
+  pub flags: String,
+  pub dry_run: bool,
+}
+pub struct Reindent {
+  pub style: IndentStyle,
+  pub indent_width: usize,
+  pub tab_width: usize,
+}
+pub struct ShellJoin;
+pub struct ShellQuote;
+pub struct ShellSplit;
+pub struct ShellUnquote;
+pub struct Slugify {
+  pub separator: String,
+  pub max_length: Option<usize>,
+}
+pub struct Smarten {
+  pub mapping: Vec<(String, char)>,
+}
+pub struct SortVersions;
+pub struct SpacesToTabs {
+  pub tab_width: usize,
+}
+pub struct SqlFormat {
+  pub indent_width: usize,
+  pub keyword_case: SqlKeywordCase,
+}
+pub struct Straighten {
+  pub mapping: BTreeMap<char, String>,
+}
+#[derive(Clone, Copy, strum::EnumIter)]
+pub enum StringLiteral {
+  Rust,
+  Python,
+  JavaScript,
+  Go,
+  Yaml,
+  Heredoc,
+}
+pub struct SummarizeNetworks;
+pub struct TabsToSpaces {
+  pub tab_width: usize,
+}
+pub struct TextToHex;
+#[derive(Clone, Copy, strum::EnumIter)]
+pub enum TokenEncoding {
+  Hex,
+  Base64Url,
+}
+pub struct TrimTrailingWhitespace;
 pub struct Unescape;
+pub struct Unwrap;
+#[derive(Clone, Copy, strum::EnumIter)]
+pub enum UuidFormat {
+  Hyphenated,
+  Simple,
+  Braced,
+  Urn,
+  Base64,
+}
+pub struct UuidTimestamp;
+pub struct XmlMinify;
+pub struct XmlPretty {
+  pub indent_width: usize,
+}